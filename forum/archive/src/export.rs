@@ -0,0 +1,236 @@
+use std::collections::BTreeSet;
+
+use hdk::prelude::{holo_hash::DnaHash, *};
+use zome_utils::{get_records_details, ZomeError};
+
+use crate::zomes::{get_channel_posts, get_comment_thread, CommentTree};
+
+// Portable copy of a channel, keeping the signed actions so that authorship can be checked offline
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChannelExport {
+    pub channel: String,
+    pub dna_hash: DnaHash,
+    pub exported_at: Timestamp,
+    // Agents that signed any of the revisions or deletes in the export
+    pub authors: Vec<AgentPubKey>,
+    pub posts: Vec<ExportedPost>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExportedPost {
+    pub history: RevisionHistory,
+    pub comments: Vec<ExportedComment>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExportedComment {
+    pub history: RevisionHistory,
+    pub replies: Vec<ExportedComment>,
+}
+
+// Every revision of an entry, each one after the revision it updates, with the actions that
+// deleted any of them
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevisionHistory {
+    pub revisions: Vec<Record>,
+    pub deletes: Vec<SignedActionHashed>,
+}
+
+// Exports all the posts in the given channel with their comments, with the signed actions of
+// all their revisions and deletes
+#[hdk_extern]
+pub fn export_channel(channel: String) -> ExternResult<ChannelExport> {
+    let mut posts: Vec<ExportedPost> = vec![];
+
+    for post_hash in get_channel_posts(channel.clone())? {
+        let history = get_revision_history(post_hash.clone())?;
+        let comments = export_comment_trees(get_comment_thread(post_hash)?)?;
+
+        posts.push(ExportedPost { history, comments });
+    }
+
+    let mut authors: BTreeSet<AgentPubKey> = BTreeSet::new();
+    for post in posts.iter() {
+        collect_authors(&post.history, &post.comments, &mut authors);
+    }
+
+    Ok(ChannelExport {
+        channel,
+        dna_hash: dna_info()?.hash,
+        exported_at: sys_time()?,
+        authors: authors.into_iter().collect(),
+        posts,
+    })
+}
+
+// Exports all the posts in the given channel with their comments, as JSON
+#[hdk_extern]
+pub fn export_channel_json(channel: String) -> ExternResult<String> {
+    let export = export_channel(channel)?;
+
+    serde_json::to_string(&export).map_err(|err| {
+        ZomeError::Internal(format!("Could not serialize the export: {}", err)).into()
+    })
+}
+
+// Checks that every action in the given export is signed by its author, that every entry
+// matches the hash in its action, that the revisions and deletes of each history belong to
+// its original record, and that the authors are exactly the agents that signed the actions
+#[hdk_extern]
+pub fn verify_channel_export(export: ChannelExport) -> ExternResult<bool> {
+    for post in export.posts.iter() {
+        if !verify_history(&post.history)? || !verify_comments(&post.comments)? {
+            return Ok(false);
+        }
+    }
+
+    let mut signers: BTreeSet<AgentPubKey> = BTreeSet::new();
+    for post in export.posts.iter() {
+        collect_authors(&post.history, &post.comments, &mut signers);
+    }
+
+    let authors: BTreeSet<AgentPubKey> = export.authors.into_iter().collect();
+
+    Ok(authors.eq(&signers))
+}
+
+fn verify_comments(comments: &[ExportedComment]) -> ExternResult<bool> {
+    for comment in comments {
+        if !verify_history(&comment.history)? || !verify_comments(&comment.replies)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+fn verify_history(history: &RevisionHistory) -> ExternResult<bool> {
+    let Some(original) = history.revisions.first() else {
+        return Ok(false);
+    };
+
+    if !matches!(original.action(), Action::Create(_)) {
+        return Ok(false);
+    }
+
+    // Every update must revise the original or one of the revisions that came before it
+    let mut revision_hashes: BTreeSet<&ActionHash> = BTreeSet::new();
+
+    for record in history.revisions.iter() {
+        if !verify_signed_action(record.signed_action())? {
+            return Ok(false);
+        }
+
+        if let (Some(entry), Some(entry_hash)) =
+            (record.entry().as_option(), record.action().entry_hash())
+        {
+            if hash_entry(entry.clone())?.ne(entry_hash) {
+                return Ok(false);
+            }
+        }
+
+        if let Action::Update(update) = record.action() {
+            if !revision_hashes.contains(&update.original_action_address) {
+                return Ok(false);
+            }
+        } else if !revision_hashes.is_empty() {
+            return Ok(false);
+        }
+
+        revision_hashes.insert(record.action_address());
+    }
+
+    for delete in history.deletes.iter() {
+        if !verify_signed_action(delete)? {
+            return Ok(false);
+        }
+
+        match delete.action() {
+            Action::Delete(delete_action)
+                if revision_hashes.contains(&delete_action.deletes_address) => {}
+            _ => return Ok(false),
+        }
+    }
+
+    Ok(true)
+}
+
+// The action hash is checked too, so that the revisions can't be chained through forged hashes
+fn verify_signed_action(signed_action: &SignedActionHashed) -> ExternResult<bool> {
+    if hash_action(signed_action.action().clone())?.ne(signed_action.action_address()) {
+        return Ok(false);
+    }
+
+    verify_signature(
+        signed_action.action().author().clone(),
+        signed_action.signature().clone(),
+        signed_action.action(),
+    )
+}
+
+fn export_comment_trees(comment_trees: Vec<CommentTree>) -> ExternResult<Vec<ExportedComment>> {
+    comment_trees
+        .into_iter()
+        .map(|comment_tree| {
+            Ok(ExportedComment {
+                history: get_revision_history(comment_tree.comment.original_action_hash)?,
+                replies: export_comment_trees(comment_tree.replies)?,
+            })
+        })
+        .collect()
+}
+
+// Walks all the updates of the given record, fetching each level of updates in a single batched call
+//
+// The revisions are kept in the order they are walked in, so that each one comes after the revision
+// it updates regardless of the timestamps their authors gave them
+fn get_revision_history(original_hash: ActionHash) -> ExternResult<RevisionHistory> {
+    let mut revisions: Vec<Record> = vec![];
+    let mut deletes: Vec<SignedActionHashed> = vec![];
+    let mut pending_hashes: Vec<ActionHash> = vec![original_hash];
+
+    while !pending_hashes.is_empty() {
+        let records_details = get_records_details(pending_hashes, GetOptions::default())?;
+        pending_hashes = vec![];
+
+        for maybe_details in records_details {
+            match maybe_details {
+                Some(Details::Record(record_details)) => {
+                    deletes.extend(record_details.deletes);
+
+                    pending_hashes.extend(
+                        record_details
+                            .updates
+                            .iter()
+                            .map(|update| update.action_address().clone()),
+                    );
+                    revisions.push(record_details.record);
+                }
+                Some(Details::Entry(_)) => {
+                    return Err(ZomeError::Malformed("Malformed details".into()).into())
+                }
+                None => return Err(ZomeError::NotFound("Revision not found".into()).into()),
+            }
+        }
+    }
+
+    Ok(RevisionHistory { revisions, deletes })
+}
+
+fn collect_authors(
+    history: &RevisionHistory,
+    comments: &[ExportedComment],
+    authors: &mut BTreeSet<AgentPubKey>,
+) {
+    for record in history.revisions.iter() {
+        authors.insert(record.action().author().clone());
+    }
+
+    for delete in history.deletes.iter() {
+        authors.insert(delete.action().author().clone());
+    }
+
+    for comment in comments {
+        collect_authors(&comment.history, &comment.replies, authors);
+    }
+}
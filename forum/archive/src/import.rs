@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+
+use hdk::prelude::*;
+use zome_utils::{get_typed_entry, ZomeError};
+
+use crate::{
+    zomes::{create_comment, create_post, create_profile, get_my_profile, Post, Profile},
+    EntryTypes, ImportedItem, LinkTypes,
+};
+
+// Dump of the forum that is being migrated
+//
+// Timestamps are given in microseconds since the UNIX epoch
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ForumArchive {
+    // Identifies the forum the dump comes from, so that its ids don't collide with the ones
+    // of the dumps of other forums
+    pub forum_id: String,
+    #[serde(default)]
+    pub users: Vec<ArchivedUser>,
+    // Id of the user that is running the import, whose profile will be created for our agent
+    #[serde(default)]
+    pub importer: Option<String>,
+    #[serde(default)]
+    pub channels: Vec<ArchivedChannel>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ArchivedUser {
+    pub id: String,
+    pub nickname: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ArchivedChannel {
+    pub name: String,
+    #[serde(default)]
+    pub posts: Vec<ArchivedPost>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ArchivedPost {
+    pub id: String,
+    pub author: String,
+    pub title: String,
+    pub content: String,
+    pub created_at: Timestamp,
+    #[serde(default)]
+    pub comments: Vec<ArchivedComment>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ArchivedComment {
+    pub id: String,
+    pub author: String,
+    pub content: String,
+    pub created_at: Timestamp,
+    #[serde(default)]
+    pub replies: Vec<ArchivedComment>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ImportSummary {
+    pub imported: u32,
+    // Items that were already imported by a previous run
+    pub skipped: u32,
+}
+
+// Imports the given JSON dump, skipping everything that was already imported
+//
+// All the content is authored by our agent, the original authors and timestamps
+// are kept as metadata of each imported item.
+// The posts and comments go through the rate limits of their zomes, so when those are set
+// a dump with more items than the limits allow is rejected as a whole: split it into smaller
+// dumps imported one rate limit period apart, since the items already imported are skipped.
+// They are also created with the same side effects as any other post or comment: the agents
+// that hold the nicknames mentioned in them today are linked to them as mentioned, the posts
+// are indexed for search, and the subscribers of their channels receive a new post signal
+#[hdk_extern]
+pub fn import_forum_archive(archive_json: String) -> ExternResult<ImportSummary> {
+    let archive: ForumArchive = serde_json::from_str(&archive_json)
+        .map_err(|err| ZomeError::InvalidRequest(format!("Malformed forum archive: {}", err)))?;
+
+    if archive.forum_id.trim().is_empty() {
+        return Err(
+            ZomeError::InvalidRequest("The forum archive must have a forum_id".into()).into(),
+        );
+    }
+
+    let nicknames: BTreeMap<String, String> = archive
+        .users
+        .into_iter()
+        .map(|user| (user.id, user.nickname))
+        .collect();
+
+    let mut summary = ImportSummary::default();
+
+    let forum_id = archive.forum_id;
+
+    if let Some(importer) = archive.importer {
+        import_profile(&forum_id, &importer, &nicknames, &mut summary)?;
+    }
+
+    for channel in archive.channels {
+        for post in channel.posts {
+            let post_hash = import_post(&forum_id, &channel.name, &post, &nicknames, &mut summary)?;
+
+            for comment in post.comments {
+                import_comment(
+                    &forum_id,
+                    post_hash.clone(),
+                    comment,
+                    &nicknames,
+                    &mut summary,
+                )?;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+fn import_profile(
+    forum_id: &str,
+    user_id: &str,
+    nicknames: &BTreeMap<String, String>,
+    summary: &mut ImportSummary,
+) -> ExternResult<()> {
+    let source_id = format!("{}/profile:{}", forum_id, user_id);
+
+    if get_my_profile()?.is_some() || find_imported(&source_id)?.is_some() {
+        summary.skipped += 1;
+        return Ok(());
+    }
+
+    let nickname = nicknames
+        .get(user_id)
+        .cloned()
+        .ok_or(ZomeError::InvalidRequest(format!(
+            "The importer {} is not among the users",
+            user_id
+        )))?;
+
+    let action_hash = create_profile(Profile {
+        nickname: nickname.clone(),
+    })?;
+
+    record_imported(source_id, Some(nickname), None, action_hash)?;
+    summary.imported += 1;
+
+    Ok(())
+}
+
+fn import_post(
+    forum_id: &str,
+    channel: &str,
+    post: &ArchivedPost,
+    nicknames: &BTreeMap<String, String>,
+    summary: &mut ImportSummary,
+) -> ExternResult<ActionHash> {
+    let source_id = format!("{}/post:{}", forum_id, post.id);
+
+    if let Some(post_hash) = find_imported(&source_id)? {
+        summary.skipped += 1;
+        return Ok(post_hash);
+    }
+
+    let post_hash = create_post(
+        Post {
+            title: post.title.clone(),
+            content: post.content.clone(),
+            attachments: vec![],
+        },
+        channel.to_string(),
+    )?;
+
+    record_imported(
+        source_id,
+        nicknames.get(&post.author).cloned(),
+        Some(post.created_at),
+        post_hash.clone(),
+    )?;
+    summary.imported += 1;
+
+    Ok(post_hash)
+}
+
+fn import_comment(
+    forum_id: &str,
+    comment_on: ActionHash,
+    comment: ArchivedComment,
+    nicknames: &BTreeMap<String, String>,
+    summary: &mut ImportSummary,
+) -> ExternResult<()> {
+    let source_id = format!("{}/comment:{}", forum_id, comment.id);
+
+    let comment_hash = match find_imported(&source_id)? {
+        Some(comment_hash) => {
+            summary.skipped += 1;
+            comment_hash
+        }
+        None => {
+            let comment_hash = create_comment(comment_on, comment.content)?;
+
+            record_imported(
+                source_id,
+                nicknames.get(&comment.author).cloned(),
+                Some(comment.created_at),
+                comment_hash.clone(),
+            )?;
+            summary.imported += 1;
+
+            comment_hash
+        }
+    };
+
+    for reply in comment.replies {
+        import_comment(forum_id, comment_hash.clone(), reply, nicknames, summary)?;
+    }
+
+    Ok(())
+}
+
+pub fn source_path(source_id: &str) -> Path {
+    let mut path = Path::from("imports");
+    path.append_component(source_id.into());
+
+    path
+}
+
+// Gets the action hash of the item we imported for the given source id, if we already imported it
+//
+// Imports by other agents are ignored, so that they can't make us skip any item
+fn find_imported(source_id: &str) -> ExternResult<Option<ActionHash>> {
+    let my_pub_key = agent_info()?.agent_initial_pubkey;
+
+    let links = get_links(
+        source_path(source_id).path_entry_hash()?,
+        LinkTypes::SourceToImportedItem,
+        None,
+    )?;
+
+    let Some(link) = links
+        .into_iter()
+        .filter(|link| link.author.eq(&my_pub_key))
+        .min_by_key(|link| link.timestamp)
+    else {
+        return Ok(None);
+    };
+
+    let maybe_imported_item: Option<ImportedItem> =
+        get_typed_entry(ActionHash::from(link.target), GetOptions::default())?;
+
+    Ok(maybe_imported_item.map(|imported_item| imported_item.imported_as))
+}
+
+fn record_imported(
+    source_id: String,
+    original_author: Option<String>,
+    original_created_at: Option<Timestamp>,
+    imported_as: ActionHash,
+) -> ExternResult<()> {
+    let path = source_path(&source_id);
+
+    let action_hash = create_entry(EntryTypes::ImportedItem(ImportedItem {
+        source_id,
+        original_author,
+        original_created_at,
+        imported_as: imported_as.clone(),
+    }))?;
+
+    create_link(
+        path.path_entry_hash()?,
+        action_hash.clone(),
+        LinkTypes::SourceToImportedItem,
+        (),
+    )?;
+
+    create_link(imported_as, action_hash, LinkTypes::ImportedToMetadata, ())?;
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use hdk::prelude::*;
+use zome_utils::{get_typed_entry, record_to_entry};
+
+mod export;
+mod import;
+mod zomes;
+
+use import::source_path;
+
+// Metadata of an item imported from another forum
+#[hdk_entry_helper]
+pub struct ImportedItem {
+    pub source_id: String,
+    // Nickname of the author in the original forum
+    pub original_author: Option<String>,
+    pub original_created_at: Option<Timestamp>,
+    pub imported_as: ActionHash,
+}
+
+#[hdk_entry_defs]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+    #[entry_def(name = "imported_item")]
+    ImportedItem(ImportedItem),
+}
+
+#[hdk_link_types]
+pub enum LinkTypes {
+    SourceToImportedItem,
+    ImportedToMetadata,
+}
+
+// Gets the original author and timestamp of the given post, comment or profile, if it was imported
+#[hdk_extern]
+pub fn get_import_metadata(action_hash: ActionHash) -> ExternResult<Option<ImportedItem>> {
+    let links = get_links(action_hash, LinkTypes::ImportedToMetadata, None)?;
+
+    match links.into_iter().min_by_key(|link| link.timestamp) {
+        Some(link) => get_typed_entry(ActionHash::from(link.target), GetOptions::default()),
+        None => Ok(None),
+    }
+}
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    match op.to_type::<EntryTypes, LinkTypes>()? {
+        OpType::StoreEntry(OpEntry::CreateEntry {
+            app_entry: EntryTypes::ImportedItem(imported_item),
+            action,
+        }) => validate_create_imported_item(action, imported_item),
+        OpType::RegisterUpdate(OpUpdate::Entry { .. }) => Ok(ValidateCallbackResult::Invalid(
+            "Imported items cannot be updated".into(),
+        )),
+        OpType::RegisterDelete(OpDelete::Entry { .. }) => Ok(ValidateCallbackResult::Invalid(
+            "Imported items cannot be deleted".into(),
+        )),
+        OpType::RegisterCreateLink {
+            link_type,
+            base_address,
+            target_address,
+            action,
+            ..
+        } => validate_create_link_imported_item(link_type, action, base_address, target_address),
+        OpType::RegisterDeleteLink { .. } => Ok(ValidateCallbackResult::Invalid(
+            "Imported items cannot be detached".into(),
+        )),
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+// Only the agent that imported an item can describe where it came from
+fn validate_create_imported_item(
+    action: Create,
+    imported_item: ImportedItem,
+) -> ExternResult<ValidateCallbackResult> {
+    let imported_record = must_get_valid_record(imported_item.imported_as)?;
+
+    match imported_record.action().author().eq(&action.author) {
+        true => Ok(ValidateCallbackResult::Valid),
+        false => Ok(ValidateCallbackResult::Invalid(
+            "Only the author of an imported item can record its metadata".into(),
+        )),
+    }
+}
+
+// Imported items can only be linked by the agent that imported them,
+// from their source id and from the item they were imported as
+fn validate_create_link_imported_item(
+    link_type: LinkTypes,
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(imported_item_hash) = target_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Import links must point to an imported item".into(),
+        ));
+    };
+    let record = must_get_valid_record(imported_item_hash)?;
+
+    let Ok(imported_item) = record_to_entry::<ImportedItem>(&record) else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Import links must point to an imported item".into(),
+        ));
+    };
+
+    if record.action().author().ne(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the agent that imported an item can link it".into(),
+        ));
+    }
+
+    let expected_base = match link_type {
+        LinkTypes::SourceToImportedItem => {
+            AnyLinkableHash::from(source_path(&imported_item.source_id).path_entry_hash()?)
+        }
+        LinkTypes::ImportedToMetadata => AnyLinkableHash::from(imported_item.imported_as),
+    };
+
+    match expected_base.eq(&base_address) {
+        true => Ok(ValidateCallbackResult::Valid),
+        false => Ok(ValidateCallbackResult::Invalid(
+            "Imported items can only be linked from their source and their copy".into(),
+        )),
+    }
+}
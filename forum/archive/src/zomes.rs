@@ -0,0 +1,96 @@
+use hdk::prelude::*;
+use zome_utils::call_local_zome;
+
+// Names of the zomes in this DNA that hold the forum content
+const PROFILES_ZOME_NAME: &str = "profiles_zome";
+const POSTS_ZOME_NAME: &str = "posts_zome";
+const COMMENTS_ZOME_NAME: &str = "comments_zome";
+
+// Mirrors the profile entry defined in the profiles zome
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Profile {
+    pub nickname: String,
+}
+
+// Mirrors the post entry defined in the posts zome
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Post {
+    pub title: String,
+    pub content: String,
+    #[serde(default)]
+    pub attachments: Vec<EntryHash>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CreatePostInput {
+    post: Post,
+    channel: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CreateCommentInput {
+    comment_on: ActionHash,
+    comment: String,
+    attachments: Vec<EntryHash>,
+}
+
+pub fn get_my_profile() -> ExternResult<Option<Profile>> {
+    call_local_zome(PROFILES_ZOME_NAME, "get_my_profile", ())
+}
+
+pub fn create_profile(profile: Profile) -> ExternResult<ActionHash> {
+    call_local_zome(PROFILES_ZOME_NAME, "create_profile", profile)
+}
+
+pub fn create_post(post: Post, channel: String) -> ExternResult<ActionHash> {
+    call_local_zome(
+        POSTS_ZOME_NAME,
+        "create_post",
+        CreatePostInput { post, channel },
+    )
+}
+
+pub fn create_comment(comment_on: ActionHash, comment: String) -> ExternResult<ActionHash> {
+    call_local_zome(
+        COMMENTS_ZOME_NAME,
+        "create_comment",
+        CreateCommentInput {
+            comment_on,
+            comment,
+            attachments: vec![],
+        },
+    )
+}
+
+// Mirrors the comment trees returned by the comments zome, keeping only what the export needs
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommentTree {
+    pub comment: LatestComment,
+    pub replies: Vec<CommentTree>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LatestComment {
+    pub original_action_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct GetCommentThreadInput {
+    root: ActionHash,
+    max_depth: u32,
+}
+
+pub fn get_channel_posts(channel: String) -> ExternResult<Vec<ActionHash>> {
+    call_local_zome(POSTS_ZOME_NAME, "get_channel_posts", channel)
+}
+
+pub fn get_comment_thread(root: ActionHash) -> ExternResult<Vec<CommentTree>> {
+    call_local_zome(
+        COMMENTS_ZOME_NAME,
+        "get_comment_thread",
+        GetCommentThreadInput {
+            root,
+            max_depth: u32::MAX,
+        },
+    )
+}
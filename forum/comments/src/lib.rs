@@ -1,14 +1,46 @@
 use hdk::prelude::*;
+#[cfg(not(feature = "exercise2step3"))]
+use std::collections::BTreeMap;
+
+#[cfg(not(feature = "exercise2step1"))]
+mod profiles;
+#[cfg(not(feature = "exercise2step1"))]
+mod properties;
+#[cfg(not(feature = "exercise2step1"))]
+mod signals;
+#[cfg(not(feature = "exercise2step1"))]
+mod unread;
+
+#[cfg(not(feature = "exercise2step1"))]
+use profiles::{get_agent_profile, Profile};
+#[cfg(not(feature = "exercise2step1"))]
+use properties::Properties;
+#[cfg(not(feature = "exercise2step1"))]
+use unread::ThreadRead;
+#[cfg(not(feature = "exercise2step1"))]
+use zome_utils::{
+    create_mention_links, dna_properties, get_typed_entry, is_moderator, is_post_or_poll,
+    validate_attachments, validate_create_link_mention, validate_create_link_subscriber,
+    validate_delete_link_subscriber, validate_rate_limit, Mention,
+};
+#[cfg(not(feature = "exercise2step3"))]
+use zome_utils::{get_latest_revisions, get_link_details_batch, get_records_details, ZomeError};
 
 #[hdk_entry_helper]
 pub struct Comment {
     pub comment: String,
+    // Manifest hashes of the files in the files zome
+    #[cfg(not(feature = "exercise2step1"))]
+    #[serde(default)]
+    pub attachments: Vec<EntryHash>,
 }
 
 #[hdk_link_types]
 #[cfg(not(feature = "exercise2step3"))]
 pub enum LinkTypes {
     CommentedOnToComment,
+    MentionToAgent,
+    ThreadToSubscriber,
 }
 
 #[hdk_entry_defs]
@@ -17,6 +49,8 @@ pub enum LinkTypes {
 pub enum EntryTypes {
     #[entry_def(name = "comment")]
     Comment(Comment),
+    #[entry_def(name = "thread_read", visibility = "private")]
+    ThreadRead(ThreadRead),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -24,6 +58,8 @@ pub enum EntryTypes {
 pub struct CreateCommentInput {
     comment_on: ActionHash,
     comment: String,
+    #[serde(default)]
+    attachments: Vec<EntryHash>,
 }
 
 // Creates a new Comment entry, associating it with the "comment_on" header
@@ -31,7 +67,8 @@ pub struct CreateCommentInput {
 #[cfg(not(feature = "exercise2step1"))]
 pub fn create_comment(input: CreateCommentInput) -> ExternResult<ActionHash> {
     let action_hash = create_entry(EntryTypes::Comment(Comment {
-        comment: input.comment,
+        comment: input.comment.clone(),
+        attachments: input.attachments,
     }))?;
 
     create_link(
@@ -41,38 +78,618 @@ pub fn create_comment(input: CreateCommentInput) -> ExternResult<ActionHash> {
         (),
     )?;
 
+    create_mention_links(
+        &input.comment,
+        None,
+        action_hash.clone(),
+        LinkTypes::MentionToAgent,
+    )?;
+
+    Ok(action_hash)
+}
+
+// Gets the comments that have mentioned us since the given time, newest first
+#[hdk_extern]
+#[cfg(not(feature = "exercise2step1"))]
+pub fn get_my_mentions(since: Timestamp) -> ExternResult<Vec<Mention>> {
+    zome_utils::get_my_mentions(since, LinkTypes::MentionToAgent)
+}
+
+// The latest revision of a comment, or a placeholder with no record if it has been deleted
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LatestComment {
+    pub original_action_hash: ActionHash,
+    pub author: AgentPubKey,
+    pub record: Option<Record>,
+    pub edited_at: Option<Timestamp>,
+    pub deleted_at: Option<Timestamp>,
+}
+
+// Gets the latest revision of all the "Comment" entries that have been associated with the given header
+#[hdk_extern]
+#[cfg(not(feature = "exercise2step3"))]
+pub fn get_comments_on(action_hash: ActionHash) -> ExternResult<Vec<LatestComment>> {
+    let comment_links = get_comment_links_on(action_hash)?;
+
+    get_comments(comment_links)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg(not(feature = "exercise2step1"))]
+pub struct CommentWithAuthor {
+    pub comment: LatestComment,
+    pub author_profile: Option<Profile>,
+}
+
+// Gets the comments associated with the given header, each one with the profile of its author
+#[hdk_extern]
+#[cfg(not(feature = "exercise2step1"))]
+pub fn get_comments_with_authors(action_hash: ActionHash) -> ExternResult<Vec<CommentWithAuthor>> {
+    let comments = get_comments_on(action_hash)?;
+
+    let mut profiles: BTreeMap<AgentPubKey, Option<Profile>> = BTreeMap::new();
+    let mut comments_with_authors: Vec<CommentWithAuthor> = vec![];
+
+    for comment in comments {
+        let author_profile = match profiles.get(&comment.author) {
+            Some(profile) => profile.clone(),
+            None => {
+                let profile = get_agent_profile(comment.author.clone())?;
+                profiles.insert(comment.author.clone(), profile.clone());
+                profile
+            }
+        };
+
+        comments_with_authors.push(CommentWithAuthor {
+            comment,
+            author_profile,
+        });
+    }
+
+    Ok(comments_with_authors)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg(not(feature = "exercise2step1"))]
+pub struct UpdateCommentInput {
+    comment_to_update: ActionHash,
+    updated_comment: Comment,
+}
+
+// Updates the given comment with the new contents
+//
+// The agents mentioned for the first time in the new revision are linked to it
+#[hdk_extern]
+#[cfg(not(feature = "exercise2step1"))]
+pub fn update_comment(input: UpdateCommentInput) -> ExternResult<ActionHash> {
+    let previous_comment: Option<Comment> =
+        get_typed_entry(input.comment_to_update.clone(), GetOptions::default())?;
+
+    let action_hash = update_entry(input.comment_to_update, &input.updated_comment)?;
+
+    create_mention_links(
+        &input.updated_comment.comment,
+        previous_comment
+            .as_ref()
+            .map(|comment| comment.comment.as_str()),
+        action_hash.clone(),
+        LinkTypes::MentionToAgent,
+    )?;
+
     Ok(action_hash)
 }
 
-// Gets all the "Comment" entries that have been associated with the given header
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommentTree {
+    pub comment: LatestComment,
+    pub reply_count: usize,
+    pub replies: Vec<CommentTree>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg(not(feature = "exercise2step3"))]
+pub struct GetCommentThreadInput {
+    root: ActionHash,
+    max_depth: u32,
+}
+
+// Gets the comments on the given header with their nested replies, up to "max_depth" levels deep
 #[hdk_extern]
 #[cfg(not(feature = "exercise2step3"))]
-pub fn get_comments_on(action_hash: ActionHash) -> ExternResult<Vec<Record>> {
-    let links = get_links(action_hash, LinkTypes::CommentedOnToComment, None)?;
+pub fn get_comment_thread(input: GetCommentThreadInput) -> ExternResult<Vec<CommentTree>> {
+    if input.max_depth == 0 {
+        return Ok(vec![]);
+    }
+
+    let comment_links = get_comment_links_on(input.root)?;
+
+    get_comment_trees(comment_links, input.max_depth - 1)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum CommentsOrder {
+    OldestFirst,
+    NewestFirst,
+}
+
+// Position of the last comment of a page, from which the next page starts
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommentsCursor {
+    timestamp: Timestamp,
+    comment_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg(not(feature = "exercise2step3"))]
+pub struct GetCommentsPageInput {
+    target: ActionHash,
+    cursor: Option<CommentsCursor>,
+    limit: usize,
+    order: CommentsOrder,
+}
 
-    let action_hashes: Vec<ActionHash> = links
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommentsPage {
+    pub comments: Vec<LatestComment>,
+    pub next_cursor: Option<CommentsCursor>,
+}
+
+// Gets a page of the comments on the given header, sorted by the time they were made
+//
+// Only the comments in the requested page are fetched, starting after the given cursor
+#[hdk_extern]
+#[cfg(not(feature = "exercise2step3"))]
+pub fn get_comments_page(input: GetCommentsPageInput) -> ExternResult<CommentsPage> {
+    if input.limit == 0 {
+        return Err(
+            ZomeError::InvalidRequest("The page limit must be greater than zero".into()).into(),
+        );
+    }
+
+    let mut comment_links = get_comment_links_on(input.target)?;
+
+    comment_links
+        .sort_by(|a, b| (a.timestamp, &a.comment_hash).cmp(&(b.timestamp, &b.comment_hash)));
+    if let CommentsOrder::NewestFirst = input.order {
+        comment_links.reverse();
+    }
+
+    let mut remaining_links = comment_links
         .into_iter()
-        .map(|link| ActionHash::from(link.target))
-        .collect();
+        .skip_while(|comment_link| match &input.cursor {
+            None => false,
+            Some(cursor) => {
+                let position = (comment_link.timestamp, &comment_link.comment_hash);
+                let cursor_position = (cursor.timestamp, &cursor.comment_hash);
+
+                match input.order {
+                    CommentsOrder::OldestFirst => position <= cursor_position,
+                    CommentsOrder::NewestFirst => position >= cursor_position,
+                }
+            }
+        })
+        .peekable();
 
-    let mut comments: Vec<Record> = vec![];
+    let mut comments: Vec<LatestComment> = vec![];
+    let mut last_cursor: Option<CommentsCursor> = None;
+
+    // Detached comments are left out of the page, so keep fetching until it's full
+    while comments.len() < input.limit {
+        let page_links: Vec<CommentLink> = remaining_links
+            .by_ref()
+            .take(input.limit - comments.len())
+            .collect();
+
+        let Some(last_link) = page_links.last() else {
+            break;
+        };
+
+        last_cursor = Some(CommentsCursor {
+            timestamp: last_link.timestamp,
+            comment_hash: last_link.comment_hash.clone(),
+        });
+
+        comments.extend(get_comments(page_links)?);
+    }
+
+    let next_cursor = match remaining_links.peek() {
+        Some(_) => last_cursor,
+        None => None,
+    };
+
+    Ok(CommentsPage {
+        comments,
+        next_cursor,
+    })
+}
+
+// Counts the comments on each of the given headers, including all their nested replies
+//
+// Only the links are walked. `delete_comment` detaches the comments it deletes, which are still
+// counted since they are kept in the threads as placeholders, so every link that was ever created
+// is counted
+#[hdk_extern]
+#[cfg(not(feature = "exercise2step3"))]
+pub fn count_comments(action_hashes: Vec<ActionHash>) -> ExternResult<BTreeMap<ActionHash, usize>> {
+    let mut counts: BTreeMap<ActionHash, usize> = BTreeMap::new();
 
     for action_hash in action_hashes {
-        let maybe_element = get(action_hash, GetOptions::default())?;
+        let mut counted: BTreeSet<ActionHash> = BTreeSet::new();
+        let mut level: Vec<ActionHash> = vec![action_hash.clone()];
+
+        while !level.is_empty() {
+            let comment_links: Vec<CommentLink> =
+                get_link_details_batch(level, LinkTypes::CommentedOnToComment)?
+                    .into_iter()
+                    .flat_map(to_comment_links)
+                    .collect();
 
-        if let Some(element) = maybe_element {
-            comments.push(element);
+            level = comment_links
+                .into_iter()
+                .map(|comment_link| comment_link.comment_hash)
+                .filter(|comment_hash| counted.insert(comment_hash.clone()))
+                .collect();
         }
+
+        counts.insert(action_hash, counted.len());
+    }
+
+    Ok(counts)
+}
+
+// A comment made on some header, and whether it has been detached from it
+#[cfg(not(feature = "exercise2step3"))]
+struct CommentLink {
+    comment_hash: ActionHash,
+    timestamp: Timestamp,
+    detached: bool,
+}
+
+// Gets all the comments that have ever been made on the given header, including the detached ones
+#[cfg(not(feature = "exercise2step3"))]
+fn get_comment_links_on(action_hash: ActionHash) -> ExternResult<Vec<CommentLink>> {
+    let link_details = get_link_details(action_hash, LinkTypes::CommentedOnToComment, None)?;
+
+    Ok(to_comment_links(link_details))
+}
+
+#[cfg(not(feature = "exercise2step3"))]
+fn to_comment_links(link_details: LinkDetails) -> Vec<CommentLink> {
+    link_details
+        .into_inner()
+        .into_iter()
+        .filter_map(|(create_link, delete_links)| match create_link.action() {
+            Action::CreateLink(create_link) => create_link
+                .target_address
+                .clone()
+                .into_action_hash()
+                .map(|comment_hash| CommentLink {
+                    comment_hash,
+                    timestamp: create_link.timestamp,
+                    detached: !delete_links.is_empty(),
+                }),
+            _ => None,
+        })
+        .collect()
+}
+
+// Gets the latest revision of the given comments, or placeholders for the ones that were deleted
+//
+// Comments that were detached without being deleted are not returned
+#[cfg(not(feature = "exercise2step3"))]
+fn get_comments(comment_links: Vec<CommentLink>) -> ExternResult<Vec<LatestComment>> {
+    let comment_hashes: Vec<ActionHash> = comment_links
+        .iter()
+        .map(|comment_link| comment_link.comment_hash.clone())
+        .collect();
+
+    let comments_details = get_records_details(comment_hashes, GetOptions::default())?;
+
+    let mut comments: Vec<LatestComment> = vec![];
+    let mut live_comments_details: Vec<RecordDetails> = vec![];
+    let mut live_comments_positions: Vec<usize> = vec![];
+
+    for (comment_link, maybe_details) in comment_links.into_iter().zip(comments_details) {
+        let Some(details) = maybe_details else {
+            continue;
+        };
+
+        let Details::Record(element_details) = details else {
+            return Err(ZomeError::Malformed("Malformed details".into()).into());
+        };
+
+        let deleted_at = element_details
+            .deletes
+            .first()
+            .map(|delete| delete.action().timestamp());
+
+        if deleted_at.is_none() && comment_link.detached {
+            continue;
+        }
+
+        comments.push(LatestComment {
+            original_action_hash: comment_link.comment_hash,
+            author: element_details.record.action().author().clone(),
+            record: None,
+            edited_at: None,
+            deleted_at,
+        });
+
+        if deleted_at.is_none() {
+            live_comments_positions.push(comments.len() - 1);
+            live_comments_details.push(element_details);
+        }
+    }
+
+    let latest_revisions = get_latest_revisions(live_comments_details)?;
+
+    for (position, element) in live_comments_positions.into_iter().zip(latest_revisions) {
+        comments[position].edited_at = match element.action() {
+            Action::Update(update) => Some(update.timestamp),
+            _ => None,
+        };
+        comments[position].record = Some(element);
     }
 
     Ok(comments)
 }
 
-// Deletes the given comment
+#[cfg(not(feature = "exercise2step3"))]
+fn get_comment_trees(
+    comment_links: Vec<CommentLink>,
+    remaining_depth: u32,
+) -> ExternResult<Vec<CommentTree>> {
+    let comments = get_comments(comment_links)?;
+
+    let mut trees: Vec<CommentTree> = vec![];
+
+    for comment in comments {
+        let reply_links = get_comment_links_on(comment.original_action_hash.clone())?;
+        let reply_count = reply_links.len();
+
+        let replies = match remaining_depth {
+            0 => vec![],
+            _ => get_comment_trees(reply_links, remaining_depth - 1)?,
+        };
+
+        trees.push(CommentTree {
+            comment,
+            reply_count,
+            replies,
+        });
+    }
+
+    Ok(trees)
+}
+
+// Gets the post at the root of the thread that the given post or comment belongs to
+#[hdk_extern]
+#[cfg(not(feature = "exercise2step1"))]
+pub fn get_thread_root(action_hash: ActionHash) -> ExternResult<ActionHash> {
+    let mut current_hash = action_hash;
+
+    loop {
+        let record = get(current_hash.clone(), GetOptions::default())?
+            .ok_or(ZomeError::NotFound("Content not found".into()))?;
+
+        if !is_comment(&record)? {
+            return Ok(current_hash);
+        }
+
+        let (_, commented_on_link) = get_commented_on_link(&record)?;
+
+        current_hash =
+            commented_on_link
+                .base_address
+                .into_action_hash()
+                .ok_or(ZomeError::Malformed(
+                    "Comment made on an invalid hash".into(),
+                ))?;
+    }
+}
+
+// Gets the link that attaches the given comment to the header it was made on
+//
+// `create_comment` creates this link right after the comment, so it's the next action in the
+// source chain of its author
+#[cfg(not(feature = "exercise2step1"))]
+fn get_commented_on_link(comment: &Record) -> ExternResult<(ActionHash, CreateLink)> {
+    let link_seq = comment.action().action_seq() + 1;
+
+    let activity = get_agent_activity(
+        comment.action().author().clone(),
+        ChainQueryFilter::new()
+            .sequence_range(ChainQueryFilterRange::ActionSeqRange(link_seq, link_seq)),
+        ActivityRequest::Full,
+    )?;
+
+    let comment_target = AnyLinkableHash::from(comment.action_address().clone());
+
+    for (_, action_hash) in activity.valid_activity {
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+
+        if let Action::CreateLink(create_link) = record.action() {
+            if create_link.target_address == comment_target {
+                return Ok((action_hash, create_link.clone()));
+            }
+        }
+    }
+
+    Err(ZomeError::NotFound("The comment is not attached to any header".into()).into())
+}
+
+// Deletes the given comment, detaching it from the header it was made on
 #[hdk_extern]
 #[cfg(not(feature = "exercise2step5"))]
 pub fn delete_comment(action_hash: ActionHash) -> ExternResult<()> {
+    #[cfg(not(feature = "exercise2step1"))]
+    {
+        let comment = get(action_hash.clone(), GetOptions::default())?
+            .ok_or(ZomeError::NotFound("Comment not found".into()))?;
+        let (commented_on_link_hash, _) = get_commented_on_link(&comment)?;
+
+        delete_link(commented_on_link_hash)?;
+    }
+
     delete_entry(action_hash)?;
 
     Ok(())
 }
+
+#[hdk_extern]
+#[cfg(not(feature = "exercise2step1"))]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    match op.to_type::<EntryTypes, LinkTypes>()? {
+        OpType::RegisterAgentActivity(OpActivity::CreateEntry {
+            app_entry_type: Some(UnitEntryTypes::Comment),
+            action,
+        }) => match dna_properties::<Properties>()?.comment_rate_limit {
+            Some(rate_limit) => validate_rate_limit(&action, &rate_limit),
+            None => Ok(ValidateCallbackResult::Valid),
+        },
+        OpType::StoreEntry(OpEntry::CreateEntry {
+            app_entry: EntryTypes::Comment(comment),
+            ..
+        })
+        | OpType::StoreEntry(OpEntry::UpdateEntry {
+            app_entry: EntryTypes::Comment(comment),
+            ..
+        }) => validate_attachments(&comment.attachments),
+        OpType::RegisterUpdate(OpUpdate::Entry {
+            original_action,
+            action,
+            ..
+        }) => validate_update_comment(action, original_action),
+        OpType::RegisterDelete(OpDelete::Entry {
+            original_action,
+            action,
+            ..
+        }) => validate_delete_comment(action, original_action),
+        OpType::RegisterCreateLink {
+            link_type: LinkTypes::CommentedOnToComment,
+            base_address,
+            target_address,
+            ..
+        } => validate_create_link_commented_on_to_comment(base_address, target_address),
+        OpType::RegisterDeleteLink {
+            link_type: LinkTypes::CommentedOnToComment,
+            original_action,
+            action,
+            ..
+        } => validate_delete_link_commented_on_to_comment(action, original_action),
+        OpType::RegisterCreateLink {
+            link_type: LinkTypes::MentionToAgent,
+            target_address,
+            action,
+            ..
+        } => validate_create_link_mention(&action, target_address, is_comment),
+        OpType::RegisterCreateLink {
+            link_type: LinkTypes::ThreadToSubscriber,
+            target_address,
+            action,
+            ..
+        } => validate_create_link_subscriber(action, target_address),
+        OpType::RegisterDeleteLink {
+            link_type: LinkTypes::ThreadToSubscriber,
+            original_action,
+            action,
+            ..
+        } => validate_delete_link_subscriber(action, original_action),
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+#[cfg(not(feature = "exercise2step1"))]
+fn validate_update_comment(
+    action: Update,
+    original_action: EntryCreationAction,
+) -> ExternResult<ValidateCallbackResult> {
+    match original_action.author().eq(&action.author) {
+        true => Ok(ValidateCallbackResult::Valid),
+        false => Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a comment can update it",
+        ))),
+    }
+}
+
+// Comments can only be deleted by their author or by a moderator
+#[cfg(not(feature = "exercise2step1"))]
+fn validate_delete_comment(
+    action: Delete,
+    original_action: EntryCreationAction,
+) -> ExternResult<ValidateCallbackResult> {
+    if original_action.author().eq(&action.author) || is_moderator(&action.author)? {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Only the author of a comment or a moderator can delete it",
+    )))
+}
+
+#[cfg(not(feature = "exercise2step1"))]
+fn validate_delete_link_commented_on_to_comment(
+    action: DeleteLink,
+    original_action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+    if original_action.author.eq(&action.author) || is_moderator(&action.author)? {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+
+    Ok(ValidateCallbackResult::Invalid(String::from(
+        "Only the author of a comment or a moderator can detach it",
+    )))
+}
+
+// Comments can only be made on a post or a poll, or as a reply to another comment
+#[cfg(not(feature = "exercise2step1"))]
+fn validate_create_link_commented_on_to_comment(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(commented_on_hash) = base_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Comments can only be made on an action hash".into(),
+        ));
+    };
+    let commented_on = must_get_valid_record(commented_on_hash)?;
+
+    if !is_post_or_poll(&commented_on)? && !is_comment(&commented_on)? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Comments can only be made on a post, a poll or another comment".into(),
+        ));
+    }
+
+    let Some(comment_hash) = target_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The link must point to the action hash of a comment".into(),
+        ));
+    };
+    let comment = must_get_valid_record(comment_hash)?;
+
+    match is_comment(&comment)? {
+        true => Ok(ValidateCallbackResult::Valid),
+        false => Ok(ValidateCallbackResult::Invalid(
+            "The link must point to a comment".into(),
+        )),
+    }
+}
+
+#[cfg(not(feature = "exercise2step1"))]
+fn is_comment(record: &Record) -> ExternResult<bool> {
+    let (Some(EntryType::App(app_entry_def)), Some(entry)) =
+        (record.action().entry_type(), record.entry().as_option())
+    else {
+        return Ok(false);
+    };
+
+    let app_entry = EntryTypes::deserialize_from_type(
+        app_entry_def.zome_index,
+        app_entry_def.entry_index,
+        entry,
+    )?;
+
+    Ok(matches!(app_entry, Some(EntryTypes::Comment(_))))
+}
@@ -0,0 +1,15 @@
+use hdk::prelude::*;
+use zome_utils::call_local_zome;
+
+// Name of the zome in this DNA that holds the profiles of the agents
+const PROFILES_ZOME_NAME: &str = "profiles_zome";
+
+// Mirrors the profile entry defined in the profiles zome
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Profile {
+    pub nickname: String,
+}
+
+pub fn get_agent_profile(agent_pub_key: AgentPubKey) -> ExternResult<Option<Profile>> {
+    call_local_zome(PROFILES_ZOME_NAME, "get_agent_profile", agent_pub_key)
+}
@@ -0,0 +1,8 @@
+use hdk::prelude::*;
+use zome_utils::RateLimit;
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Properties {
+    #[serde(default)]
+    pub comment_rate_limit: Option<RateLimit>,
+}
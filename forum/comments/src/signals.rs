@@ -0,0 +1,80 @@
+use hdk::prelude::*;
+use zome_utils::{grant_unrestricted_access, signal_subscribers, unsubscribe};
+
+use crate::LinkTypes;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum Signal {
+    NewComment {
+        comment_hash: ActionHash,
+        commented_on: ActionHash,
+    },
+}
+
+// Allows any agent to send us signals
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    grant_unrestricted_access("recv_remote_signal")?;
+
+    Ok(InitCallbackResult::Pass)
+}
+
+// Starts receiving signals for the new comments made on the given post or comment
+#[hdk_extern]
+pub fn subscribe_to_thread(commented_on: ActionHash) -> ExternResult<()> {
+    create_link(
+        commented_on,
+        agent_info()?.agent_initial_pubkey,
+        LinkTypes::ThreadToSubscriber,
+        (),
+    )?;
+
+    Ok(())
+}
+
+// Stops receiving signals for the new comments made on the given post or comment
+#[hdk_extern]
+pub fn unsubscribe_from_thread(commented_on: ActionHash) -> ExternResult<()> {
+    unsubscribe(commented_on, LinkTypes::ThreadToSubscriber)
+}
+
+#[hdk_extern]
+pub fn recv_remote_signal(signal: Signal) -> ExternResult<()> {
+    emit_signal(signal)
+}
+
+#[hdk_extern(infallible)]
+pub fn post_commit(committed_actions: Vec<SignedActionHashed>) {
+    if let Err(err) = signal_new_comments(committed_actions) {
+        error!("Error signaling the new comments: {:?}", err);
+    }
+}
+
+// Signals every new comment to ourselves and to the subscribers of the thread it was made on
+fn signal_new_comments(committed_actions: Vec<SignedActionHashed>) -> ExternResult<()> {
+    for action in committed_actions {
+        let Action::CreateLink(create_link) = action.action() else {
+            continue;
+        };
+
+        let Some(LinkTypes::CommentedOnToComment) =
+            LinkTypes::from_type(create_link.zome_index, create_link.link_type)?
+        else {
+            continue;
+        };
+
+        let signal = Signal::NewComment {
+            comment_hash: ActionHash::from(create_link.target_address.clone()),
+            commented_on: ActionHash::from(create_link.base_address.clone()),
+        };
+
+        signal_subscribers(
+            &signal,
+            create_link.base_address.clone(),
+            LinkTypes::ThreadToSubscriber,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use hdk::prelude::*;
+use std::collections::BTreeMap;
+use zome_utils::{get_links_batch, record_to_entry};
+
+use crate::{EntryTypes, LinkTypes, UnitEntryTypes};
+
+// Private record of the last time we read the comments on a post or comment
+#[hdk_entry_helper]
+pub struct ThreadRead {
+    pub thread: ActionHash,
+    pub read_at: Timestamp,
+}
+
+// Records that we have read all the comments on the given post or comment until now
+#[hdk_extern]
+pub fn mark_read(thread: ActionHash) -> ExternResult<()> {
+    create_entry(EntryTypes::ThreadRead(ThreadRead {
+        thread,
+        read_at: sys_time()?,
+    }))?;
+
+    Ok(())
+}
+
+// Gets the number of comments that others have made on each thread since we last read it
+#[hdk_extern]
+pub fn get_unread_counts(_: ()) -> ExternResult<BTreeMap<ActionHash, usize>> {
+    let last_reads = get_last_reads()?;
+    let my_pub_key = agent_info()?.agent_initial_pubkey;
+
+    let threads: Vec<ActionHash> = last_reads.keys().cloned().collect();
+    let threads_links = get_links_batch(threads.clone(), LinkTypes::CommentedOnToComment)?;
+
+    let mut unread_counts: BTreeMap<ActionHash, usize> = BTreeMap::new();
+
+    for (thread, links) in threads.into_iter().zip(threads_links) {
+        let read_at = last_reads[&thread];
+
+        let unread_count = links
+            .into_iter()
+            .filter(|link| link.author.ne(&my_pub_key))
+            .filter(|link| link.timestamp.gt(&read_at))
+            .count();
+
+        unread_counts.insert(thread, unread_count);
+    }
+
+    Ok(unread_counts)
+}
+
+fn get_last_reads() -> ExternResult<BTreeMap<ActionHash, Timestamp>> {
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::ThreadRead.try_into()?)
+            .include_entries(true),
+    )?;
+
+    let mut last_reads: BTreeMap<ActionHash, Timestamp> = BTreeMap::new();
+
+    for record in records {
+        let thread_read: ThreadRead = record_to_entry(&record)?;
+
+        let last_read = last_reads
+            .entry(thread_read.thread)
+            .or_insert(thread_read.read_at);
+
+        if thread_read.read_at.gt(last_read) {
+            *last_read = thread_read.read_at;
+        }
+    }
+
+    Ok(last_reads)
+}
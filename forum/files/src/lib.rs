@@ -0,0 +1,188 @@
+use hdk::prelude::*;
+use zome_utils::{get_records, get_typed_entry, record_to_entry, ZomeError};
+
+mod properties;
+
+use properties::forum_properties;
+
+// A piece of the contents of a file
+#[hdk_entry_helper]
+pub struct FileChunk {
+    #[serde(with = "serde_bytes")]
+    pub bytes: Vec<u8>,
+}
+
+// Describes a file and the chunks that its contents were split into, in order
+#[hdk_entry_helper]
+pub struct FileManifest {
+    pub name: String,
+    pub mime_type: String,
+    pub size: usize,
+    pub chunks: Vec<EntryHash>,
+}
+
+#[hdk_entry_defs]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+    #[entry_def(name = "file_chunk")]
+    FileChunk(FileChunk),
+    #[entry_def(name = "file_manifest")]
+    FileManifest(FileManifest),
+}
+
+// Creates a single chunk, for clients that upload big files in several calls
+#[hdk_extern]
+pub fn create_file_chunk(chunk: FileChunk) -> ExternResult<EntryHash> {
+    let entry_hash = hash_entry(&chunk)?;
+    create_entry(EntryTypes::FileChunk(chunk))?;
+
+    Ok(entry_hash)
+}
+
+// Creates the manifest for chunks that were already uploaded
+#[hdk_extern]
+pub fn create_file_manifest(manifest: FileManifest) -> ExternResult<EntryHash> {
+    let entry_hash = hash_entry(&manifest)?;
+    create_entry(EntryTypes::FileManifest(manifest))?;
+
+    Ok(entry_hash)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UploadFileInput {
+    name: String,
+    mime_type: String,
+    #[serde(with = "serde_bytes")]
+    bytes: Vec<u8>,
+}
+
+// Splits the given file into chunks and creates its manifest, returning the manifest hash to attach to posts and comments
+#[hdk_extern]
+pub fn upload_file(input: UploadFileInput) -> ExternResult<EntryHash> {
+    let max_chunk_size = forum_properties()?.max_chunk_size;
+
+    let chunks = input
+        .bytes
+        .chunks(max_chunk_size)
+        .map(|bytes| {
+            create_file_chunk(FileChunk {
+                bytes: bytes.to_vec(),
+            })
+        })
+        .collect::<ExternResult<Vec<EntryHash>>>()?;
+
+    create_file_manifest(FileManifest {
+        name: input.name,
+        mime_type: input.mime_type,
+        size: input.bytes.len(),
+        chunks,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct File {
+    pub manifest: FileManifest,
+    #[serde(with = "serde_bytes")]
+    pub bytes: Vec<u8>,
+}
+
+// Fetches all the chunks of the given file and puts its contents back together
+#[hdk_extern]
+pub fn get_file(manifest_hash: EntryHash) -> ExternResult<File> {
+    let manifest: FileManifest = get_typed_entry(manifest_hash, GetOptions::default())?
+        .ok_or(ZomeError::NotFound("File not found".into()))?;
+
+    let chunk_records = get_records(manifest.chunks.clone(), GetOptions::default())?;
+
+    let mut bytes: Vec<u8> = Vec::with_capacity(manifest.size);
+
+    for (chunk_hash, maybe_record) in manifest.chunks.iter().zip(chunk_records) {
+        let record = maybe_record.ok_or(ZomeError::NotFound("File chunk not found".into()))?;
+        let chunk: FileChunk = record_to_entry(&record)?;
+
+        if hash_entry(&chunk)? != *chunk_hash {
+            return Err(ZomeError::Malformed("File chunk doesn't match its hash".into()).into());
+        }
+
+        bytes.extend(chunk.bytes);
+    }
+
+    if bytes.len() != manifest.size {
+        return Err(ZomeError::Malformed(
+            "File contents don't match the size in its manifest".into(),
+        )
+        .into());
+    }
+
+    Ok(File { manifest, bytes })
+}
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    match op.to_type::<EntryTypes, ()>()? {
+        OpType::StoreEntry(OpEntry::CreateEntry { app_entry, .. }) => match app_entry {
+            EntryTypes::FileChunk(chunk) => validate_create_chunk(chunk),
+            EntryTypes::FileManifest(manifest) => validate_create_manifest(manifest),
+        },
+        OpType::RegisterUpdate(OpUpdate::Entry { .. }) => Ok(ValidateCallbackResult::Invalid(
+            "Files cannot be updated".into(),
+        )),
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+fn validate_create_chunk(chunk: FileChunk) -> ExternResult<ValidateCallbackResult> {
+    let max_chunk_size = forum_properties()?.max_chunk_size;
+
+    if chunk.bytes.len() > max_chunk_size {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "File chunks can't be bigger than {} bytes",
+            max_chunk_size
+        )));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_create_manifest(manifest: FileManifest) -> ExternResult<ValidateCallbackResult> {
+    let max_file_size = forum_properties()?.max_file_size;
+
+    if manifest.size > max_file_size {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Files can't be bigger than {} bytes",
+            max_file_size
+        )));
+    }
+
+    let mut size: usize = 0;
+
+    for chunk_hash in manifest.chunks {
+        let entry = must_get_entry(chunk_hash)?;
+
+        let chunk = match FileChunk::try_from(entry.content) {
+            Ok(chunk) => chunk,
+            Err(_) => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "A file manifest can only point to file chunks".into(),
+                ))
+            }
+        };
+
+        size = match size.checked_add(chunk.bytes.len()) {
+            Some(size) if size <= manifest.size => size,
+            _ => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The size of a file must match the size of its chunks".into(),
+                ))
+            }
+        };
+    }
+
+    if size != manifest.size {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The size of a file must match the size of its chunks".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,44 @@
+use hdk::prelude::*;
+use zome_utils::{dna_properties, ZomeError};
+
+// 10MB
+const DEFAULT_MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
+// 256KB
+const DEFAULT_MAX_CHUNK_SIZE: usize = 256 * 1024;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Properties {
+    #[serde(default = "default_max_file_size")]
+    pub max_file_size: usize,
+    #[serde(default = "default_max_chunk_size")]
+    pub max_chunk_size: usize,
+}
+
+fn default_max_file_size() -> usize {
+    DEFAULT_MAX_FILE_SIZE
+}
+
+fn default_max_chunk_size() -> usize {
+    DEFAULT_MAX_CHUNK_SIZE
+}
+
+impl Default for Properties {
+    fn default() -> Self {
+        Properties {
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            max_chunk_size: DEFAULT_MAX_CHUNK_SIZE,
+        }
+    }
+}
+
+// Reads the file properties from the DNA, rejecting the ones that can't be used
+pub fn forum_properties() -> ExternResult<Properties> {
+    let properties: Properties = dna_properties()?;
+
+    // Files couldn't be split into chunks of no size
+    if properties.max_chunk_size == 0 {
+        return Err(ZomeError::Malformed("max_chunk_size must be greater than 0".into()).into());
+    }
+
+    Ok(properties)
+}
@@ -0,0 +1,102 @@
+use hdk::prelude::*;
+use membrane_proof::{
+    membrane_proof_record, validate_membrane_proof_record, PrivatePublicationMembraneProof,
+};
+use zome_utils::{dna_properties, ZomeError};
+
+mod properties;
+
+use properties::{can_invite, Properties};
+
+#[hdk_entry_defs]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+    #[entry_def(name = "membrane_proof")]
+    MembraneProof(PrivatePublicationMembraneProof),
+}
+
+// Issues a membrane proof that lets the given agent join this forum,
+// to be handed to them out of band and used when they install the forum
+#[hdk_extern]
+pub fn create_membrane_proof_for(recipient: AgentPubKey) -> ExternResult<Record> {
+    if !can_invite(&agent_info()?.agent_initial_pubkey)? {
+        return Err(
+            ZomeError::Unauthorized("Only inviters can issue membrane proofs".into()).into(),
+        );
+    }
+
+    let action_hash = create_entry(EntryTypes::MembraneProof(PrivatePublicationMembraneProof {
+        recipient,
+        dna_hash: dna_info()?.hash,
+    }))?;
+
+    get(action_hash, GetOptions::default())?
+        .ok_or(ZomeError::NotFound("Membrane proof not found".into()).into())
+}
+
+#[hdk_extern]
+pub fn is_invite_only(_: ()) -> ExternResult<bool> {
+    Ok(dna_properties::<Properties>()?.invite_only)
+}
+
+#[hdk_extern]
+pub fn genesis_self_check(data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
+    is_membrane_proof_valid(data.agent_key, data.membrane_proof)
+}
+
+// Open forums let anyone in, invite-only forums require a membrane proof
+// issued by someone who holds invite rights
+fn is_membrane_proof_valid(
+    for_agent: AgentPubKey,
+    membrane_proof: Option<MembraneProof>,
+) -> ExternResult<ValidateCallbackResult> {
+    if !dna_properties::<Properties>()?.invite_only || can_invite(&for_agent)? {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+
+    match membrane_proof {
+        None => Ok(ValidateCallbackResult::Invalid(
+            "Invalid agent: no membrane proof present".into(),
+        )),
+        Some(proof) => {
+            let record = membrane_proof_record(proof)?;
+
+            if !can_invite(record.action().author())? {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The author of the record doesn't hold invite rights".into(),
+                ));
+            }
+
+            validate_membrane_proof_record(&for_agent, &record)
+        }
+    }
+}
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    match op.to_type::<EntryTypes, ()>()? {
+        OpType::StoreEntry(OpEntry::CreateEntry {
+            app_entry: EntryTypes::MembraneProof(_),
+            action,
+        }) => match can_invite(&action.author)? {
+            true => Ok(ValidateCallbackResult::Valid),
+            false => Ok(ValidateCallbackResult::Invalid(
+                "Only inviters can issue membrane proofs".into(),
+            )),
+        },
+        OpType::RegisterAgentActivity(OpActivity::CreateAgent { agent, action }) => {
+            let previous_action = must_get_action(action.prev_action)?;
+
+            match previous_action.action() {
+                Action::AgentValidationPkg(AgentValidationPkg { membrane_proof, .. }) => {
+                    is_membrane_proof_valid(agent, membrane_proof.clone())
+                }
+                _ => Ok(ValidateCallbackResult::Invalid(
+                    "The previous action for a `CreateAgent` action must be an `AgentValidationPkg`"
+                        .into(),
+                )),
+            }
+        }
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
@@ -0,0 +1,31 @@
+use hdk::prelude::{holo_hash::AgentPubKeyB64, *};
+use zome_utils::dna_properties;
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Properties {
+    // When set, agents need a membrane proof issued by an inviter to join the forum
+    #[serde(default)]
+    pub invite_only: bool,
+    #[serde(default)]
+    pub progenitor: Option<AgentPubKeyB64>,
+    // Members other than the progenitor that can issue membrane proofs
+    #[serde(default)]
+    pub inviters: Vec<AgentPubKeyB64>,
+}
+
+// Gets the agents that hold invite rights: the progenitor and the inviters in the DNA properties
+#[hdk_extern]
+pub fn get_inviters(_: ()) -> ExternResult<Vec<AgentPubKey>> {
+    let properties: Properties = dna_properties()?;
+
+    Ok(properties
+        .progenitor
+        .into_iter()
+        .chain(properties.inviters)
+        .map(AgentPubKey::from)
+        .collect())
+}
+
+pub fn can_invite(agent: &AgentPubKey) -> ExternResult<bool> {
+    Ok(get_inviters(())?.contains(agent))
+}
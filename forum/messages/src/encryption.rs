@@ -0,0 +1,75 @@
+use hdk::prelude::*;
+use zome_utils::call_local_zome;
+
+use crate::LinkTypes;
+
+// Name of the zome in this DNA that links the encryption keys of the agents from their profiles
+const PROFILES_ZOME_NAME: &str = "profiles_zome";
+
+// Creates a new keypair and publishes its public key so that others can encrypt messages for us,
+// the secret half never leaves our keystore
+//
+// This key is only used for agents that haven't linked an encryption key from their profile
+pub fn publish_encryption_key() -> ExternResult<X25519PubKey> {
+    let my_pub_key = agent_info()?.agent_initial_pubkey;
+
+    let key = create_x25519_keypair()?;
+
+    // The key is small enough to live in the tag of a link from our public key to itself
+    create_link(
+        my_pub_key.clone(),
+        my_pub_key,
+        LinkTypes::AgentToEncryptionKey,
+        LinkTag::new(key.as_ref().to_vec()),
+    )?;
+
+    Ok(key)
+}
+
+// Gets the key to encrypt messages for the given agent: the latest one linked from their profile,
+// falling back to the one published from `init` for the agents whose profile doesn't have any
+pub fn get_agent_encryption_key(agent: AgentPubKey) -> ExternResult<Option<X25519PubKey>> {
+    if let Some(key) = call_local_zome(
+        PROFILES_ZOME_NAME,
+        "get_agent_encryption_key",
+        agent.clone(),
+    )? {
+        return Ok(Some(key));
+    }
+
+    let links = get_links(agent, LinkTypes::AgentToEncryptionKey, None)?;
+
+    Ok(links
+        .into_iter()
+        .max_by_key(|link| link.timestamp)
+        .and_then(|link| X25519PubKey::try_from(link.tag.0.as_slice()).ok()))
+}
+
+pub fn get_my_encryption_key() -> ExternResult<X25519PubKey> {
+    match get_agent_encryption_key(agent_info()?.agent_initial_pubkey)? {
+        Some(key) => Ok(key),
+        None => publish_encryption_key(),
+    }
+}
+
+// Agents can only publish encryption keys for themselves
+pub fn validate_create_link_encryption_key(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let author = AnyLinkableHash::from(action.author);
+
+    if author.ne(&base_address) || author.ne(&target_address) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Agents can only publish encryption keys for themselves".into(),
+        ));
+    }
+
+    match X25519PubKey::try_from(action.tag.0.as_slice()) {
+        Ok(_) => Ok(ValidateCallbackResult::Valid),
+        Err(_) => Ok(ValidateCallbackResult::Invalid(
+            "Malformed encryption key".into(),
+        )),
+    }
+}
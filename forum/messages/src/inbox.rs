@@ -0,0 +1,154 @@
+use hdk::prelude::*;
+use zome_utils::{get_typed_entry, record_to_entry, ZomeError};
+
+use crate::{
+    encryption::{get_agent_encryption_key, get_my_encryption_key},
+    store_message, EntryTypes, LinkTypes, Message,
+};
+
+// Message waiting in the DHT for a recipient that was offline when it was sent,
+// only readable by the recipient
+#[hdk_entry_helper]
+pub struct InboxMessage {
+    pub sender: AgentPubKey,
+    pub recipient: AgentPubKey,
+    pub sender_key: X25519PubKey,
+    pub recipient_key: X25519PubKey,
+    pub encrypted_message: XSalsa20Poly1305EncryptedData,
+}
+
+// Encrypts the given message for its recipient and leaves it in their inbox
+pub fn send_to_inbox(message: &Message) -> ExternResult<()> {
+    let recipient_key =
+        get_agent_encryption_key(message.recipient.clone())?.ok_or(ZomeError::NotFound(
+            "The recipient is offline and hasn't published an encryption key yet".into(),
+        ))?;
+    let sender_key = get_my_encryption_key()?;
+
+    let bytes = SerializedBytes::try_from(message.clone()).map_err(|err| {
+        ZomeError::Internal(format!("Could not serialize the message: {:?}", err))
+    })?;
+
+    let encrypted_message = x_25519_x_salsa20_poly1305_encrypt(
+        sender_key,
+        recipient_key,
+        XSalsa20Poly1305Data::from(bytes.bytes().clone()),
+    )?;
+
+    let action_hash = create_entry(EntryTypes::InboxMessage(InboxMessage {
+        sender: message.sender.clone(),
+        recipient: message.recipient.clone(),
+        sender_key,
+        recipient_key,
+        encrypted_message,
+    }))?;
+
+    create_link(
+        message.recipient.clone(),
+        action_hash,
+        LinkTypes::RecipientToInboxMessage,
+        (),
+    )?;
+
+    Ok(())
+}
+
+// Decrypts the messages left in our inbox while we were offline, storing them in our source chain
+pub fn collect_inbox() -> ExternResult<()> {
+    let links = get_links(
+        agent_info()?.agent_initial_pubkey,
+        LinkTypes::RecipientToInboxMessage,
+        None,
+    )?;
+
+    for link in links {
+        let Some(inbox_message) = get_typed_entry::<InboxMessage, _>(
+            ActionHash::from(link.target),
+            GetOptions::default(),
+        )?
+        else {
+            continue;
+        };
+
+        if let Some(message) = decrypt_inbox_message(inbox_message)? {
+            store_message(message)?;
+        }
+
+        delete_link(link.create_link_hash)?;
+    }
+
+    Ok(())
+}
+
+// Only the recipient has the secret half of the key in their keystore,
+// validators only ever see the encrypted message
+fn decrypt_inbox_message(inbox_message: InboxMessage) -> ExternResult<Option<Message>> {
+    let Some(data) = x_25519_x_salsa20_poly1305_decrypt(
+        inbox_message.recipient_key,
+        inbox_message.sender_key,
+        inbox_message.encrypted_message,
+    )?
+    else {
+        return Ok(None);
+    };
+
+    let bytes = SerializedBytes::from(UnsafeBytes::from(data.as_ref().to_vec()));
+    let message = Message::try_from(bytes)
+        .map_err(|err| ZomeError::Malformed(format!("Malformed inbox message: {:?}", err)))?;
+
+    // The sender of the inbox message was validated, the contents of the message were not
+    if message.sender.ne(&inbox_message.sender) || message.recipient.ne(&inbox_message.recipient) {
+        return Ok(None);
+    }
+
+    Ok(Some(message))
+}
+
+pub fn validate_create_inbox_message(
+    action: &Create,
+    inbox_message: InboxMessage,
+) -> ExternResult<ValidateCallbackResult> {
+    match inbox_message.sender.eq(&action.author) {
+        true => Ok(ValidateCallbackResult::Valid),
+        false => Ok(ValidateCallbackResult::Invalid(
+            "Agents can only send messages from themselves".into(),
+        )),
+    }
+}
+
+// Messages can only be left in the inbox of their recipient
+pub fn validate_create_link_inbox_message(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let record = must_get_valid_record(ActionHash::from(target_address))?;
+
+    let inbox_message: InboxMessage = match record_to_entry(&record).ok() {
+        Some(inbox_message) => inbox_message,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Inbox links can only point to inbox messages".into(),
+            ))
+        }
+    };
+
+    match AnyLinkableHash::from(inbox_message.recipient).eq(&base_address) {
+        true => Ok(ValidateCallbackResult::Valid),
+        false => Ok(ValidateCallbackResult::Invalid(
+            "Messages can only be left in the inbox of their recipient".into(),
+        )),
+    }
+}
+
+// Only the recipient can clear their inbox
+pub fn validate_delete_link_inbox_message(
+    action: DeleteLink,
+    base_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    match AnyLinkableHash::from(action.author).eq(&base_address) {
+        true => Ok(ValidateCallbackResult::Valid),
+        false => Ok(ValidateCallbackResult::Invalid(
+            "Only the recipient can clear their inbox".into(),
+        )),
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+
+use hdk::prelude::*;
+use zome_utils::{grant_unrestricted_access, record_to_entry, ZomeError};
+
+mod encryption;
+mod inbox;
+
+use encryption::{publish_encryption_key, validate_create_link_encryption_key};
+use inbox::{
+    collect_inbox, send_to_inbox, validate_create_inbox_message,
+    validate_create_link_inbox_message, validate_delete_link_inbox_message, InboxMessage,
+};
+
+// Direct message, stored privately in the source chains of both the sender and the recipient
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct Message {
+    pub sender: AgentPubKey,
+    pub recipient: AgentPubKey,
+    pub body: String,
+    pub sent_at: Timestamp,
+}
+
+#[hdk_entry_defs]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+    #[entry_def(name = "message", visibility = "private")]
+    Message(Message),
+    #[entry_def(name = "inbox_message")]
+    InboxMessage(InboxMessage),
+}
+
+#[hdk_link_types]
+pub enum LinkTypes {
+    AgentToEncryptionKey,
+    RecipientToInboxMessage,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum Signal {
+    NewMessage { message: Message },
+}
+
+// Allows any agent to deliver messages to us, and publishes the key they can use to encrypt them
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    grant_unrestricted_access("receive_message")?;
+
+    publish_encryption_key()?;
+
+    Ok(InitCallbackResult::Pass)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SendMessageInput {
+    recipient: AgentPubKey,
+    body: String,
+}
+
+// Sends the given message directly to the recipient, leaving it in their inbox if they can't be reached
+#[hdk_extern]
+pub fn send_message(input: SendMessageInput) -> ExternResult<Message> {
+    let message = Message {
+        sender: agent_info()?.agent_initial_pubkey,
+        recipient: input.recipient.clone(),
+        body: input.body,
+        sent_at: sys_time()?,
+    };
+
+    create_entry(EntryTypes::Message(message.clone()))?;
+
+    let response = call_remote(
+        input.recipient,
+        zome_info()?.name,
+        "receive_message".into(),
+        None,
+        message.clone(),
+    )?;
+
+    if !matches!(response, ZomeCallResponse::Ok(_)) {
+        send_to_inbox(&message)?;
+    }
+
+    Ok(message)
+}
+
+// Called remotely by the sender of the given message
+#[hdk_extern]
+pub fn receive_message(message: Message) -> ExternResult<()> {
+    let provenance = call_info()?.provenance;
+
+    if message.sender.ne(&provenance) || message.recipient.ne(&agent_info()?.agent_initial_pubkey) {
+        return Err(ZomeError::Unauthorized(
+            "Agents can only send messages from themselves to us".into(),
+        )
+        .into());
+    }
+
+    store_message(message)
+}
+
+// Stores the given received message, unless we already have it
+pub fn store_message(message: Message) -> ExternResult<()> {
+    let entry_hash = hash_entry(&message)?;
+
+    if get_messages()?
+        .iter()
+        .any(|stored_message| hash_entry(stored_message).ok() == Some(entry_hash.clone()))
+    {
+        return Ok(());
+    }
+
+    create_entry(EntryTypes::Message(message.clone()))?;
+
+    emit_signal(Signal::NewMessage { message })
+}
+
+// Gets all the messages exchanged with the given agent, oldest first
+#[hdk_extern]
+pub fn get_conversation(agent: AgentPubKey) -> ExternResult<Vec<Message>> {
+    collect_inbox()?;
+
+    let mut messages: Vec<Message> = get_messages()?
+        .into_iter()
+        .filter(|message| message.sender.eq(&agent) || message.recipient.eq(&agent))
+        .collect();
+
+    messages.sort_by_key(|message| message.sent_at);
+
+    Ok(messages)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Conversation {
+    pub agent: AgentPubKey,
+    pub last_message: Message,
+    pub message_count: usize,
+}
+
+// Gets all the agents we have exchanged messages with, the most recently active conversation first
+#[hdk_extern]
+pub fn list_conversations(_: ()) -> ExternResult<Vec<Conversation>> {
+    collect_inbox()?;
+
+    let my_pub_key = agent_info()?.agent_initial_pubkey;
+
+    let mut conversations: BTreeMap<AgentPubKey, Conversation> = BTreeMap::new();
+
+    for message in get_messages()? {
+        let agent = match message.sender.eq(&my_pub_key) {
+            true => message.recipient.clone(),
+            false => message.sender.clone(),
+        };
+
+        match conversations.get_mut(&agent) {
+            Some(conversation) => {
+                conversation.message_count += 1;
+
+                if message.sent_at > conversation.last_message.sent_at {
+                    conversation.last_message = message;
+                }
+            }
+            None => {
+                conversations.insert(
+                    agent.clone(),
+                    Conversation {
+                        agent,
+                        last_message: message,
+                        message_count: 1,
+                    },
+                );
+            }
+        }
+    }
+
+    let mut conversations: Vec<Conversation> = conversations.into_values().collect();
+    conversations.sort_by_key(|conversation| std::cmp::Reverse(conversation.last_message.sent_at));
+
+    Ok(conversations)
+}
+
+// Messages are private so they are only found in our own source chain
+fn get_messages() -> ExternResult<Vec<Message>> {
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::Message.try_into()?)
+            .include_entries(true),
+    )?;
+
+    let mut messages: Vec<Message> = vec![];
+
+    for record in records {
+        messages.push(record_to_entry(&record)?);
+    }
+
+    Ok(messages)
+}
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    match op.to_type::<EntryTypes, LinkTypes>()? {
+        OpType::StoreEntry(OpEntry::CreateEntry {
+            app_entry: EntryTypes::InboxMessage(inbox_message),
+            action,
+        }) => validate_create_inbox_message(&action, inbox_message),
+        OpType::RegisterUpdate(OpUpdate::Entry { .. }) => Ok(ValidateCallbackResult::Invalid(
+            "Messages cannot be updated".into(),
+        )),
+        OpType::RegisterCreateLink {
+            link_type,
+            base_address,
+            target_address,
+            action,
+            ..
+        } => match link_type {
+            LinkTypes::AgentToEncryptionKey => {
+                validate_create_link_encryption_key(action, base_address, target_address)
+            }
+            LinkTypes::RecipientToInboxMessage => {
+                validate_create_link_inbox_message(base_address, target_address)
+            }
+        },
+        OpType::RegisterDeleteLink {
+            link_type,
+            base_address,
+            action,
+            ..
+        } => match link_type {
+            LinkTypes::AgentToEncryptionKey => Ok(ValidateCallbackResult::Invalid(
+                "Encryption keys cannot be unpublished".into(),
+            )),
+            LinkTypes::RecipientToInboxMessage => {
+                validate_delete_link_inbox_message(action, base_address)
+            }
+        },
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
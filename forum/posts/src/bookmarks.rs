@@ -0,0 +1,131 @@
+use hdk::prelude::*;
+use zome_utils::{get_latest_revisions, get_records_details, record_to_entry, ZomeError};
+
+use crate::{EntryTypes, UnitEntryTypes};
+
+// Private bookmark of a post, only visible in our own source chain
+#[hdk_entry_helper]
+pub struct Bookmark {
+    pub post: ActionHash,
+}
+
+// What became of a bookmarked post
+#[derive(Serialize, Deserialize, Debug)]
+pub enum SavedPostStatus {
+    // The post is still there, with its latest revision
+    Live(Record),
+    Deleted,
+    // The post couldn't be fetched, e.g. because none of its holders is reachable right now
+    NotFound,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SavedPost {
+    pub post_hash: ActionHash,
+    pub saved_at: Timestamp,
+    pub status: SavedPostStatus,
+}
+
+// Bookmarks the given post
+#[hdk_extern]
+pub fn save_post(post_hash: ActionHash) -> ExternResult<ActionHash> {
+    create_entry(EntryTypes::Bookmark(Bookmark { post: post_hash }))
+}
+
+// Removes all our bookmarks for the given post
+#[hdk_extern]
+pub fn unsave_post(post_hash: ActionHash) -> ExternResult<()> {
+    for (bookmark_hash, _, bookmark) in get_live_bookmarks()? {
+        if bookmark.post == post_hash {
+            delete_entry(bookmark_hash)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Gets all the posts we have bookmarked, with their latest revision if they are still there
+#[hdk_extern]
+pub fn get_saved_posts(_: ()) -> ExternResult<Vec<SavedPost>> {
+    let bookmarks = get_live_bookmarks()?;
+
+    let post_hashes: Vec<ActionHash> = bookmarks
+        .iter()
+        .map(|(_, _, bookmark)| bookmark.post.clone())
+        .collect();
+
+    let posts_details = get_records_details(post_hashes, GetOptions::default())?;
+
+    let mut saved_posts: Vec<SavedPost> = vec![];
+    let mut live_posts_details: Vec<RecordDetails> = vec![];
+    let mut live_posts_positions: Vec<usize> = vec![];
+
+    for ((_, saved_at, bookmark), maybe_details) in bookmarks.into_iter().zip(posts_details) {
+        let status = match maybe_details {
+            Some(Details::Record(element_details)) if !element_details.deletes.is_empty() => {
+                SavedPostStatus::Deleted
+            }
+            Some(Details::Record(element_details)) => {
+                live_posts_positions.push(saved_posts.len());
+                live_posts_details.push(element_details);
+
+                // Filled in below, once the latest revisions have been fetched
+                SavedPostStatus::NotFound
+            }
+            Some(Details::Entry(_)) => {
+                return Err(ZomeError::Malformed("Malformed details".into()).into())
+            }
+            None => SavedPostStatus::NotFound,
+        };
+
+        saved_posts.push(SavedPost {
+            post_hash: bookmark.post,
+            saved_at,
+            status,
+        });
+    }
+
+    let latest_revisions = get_latest_revisions(live_posts_details)?;
+
+    for (position, latest_revision) in live_posts_positions.into_iter().zip(latest_revisions) {
+        saved_posts[position].status = SavedPostStatus::Live(latest_revision);
+    }
+
+    Ok(saved_posts)
+}
+
+// Gets the bookmarks in our source chain that haven't been deleted, with the time they were created
+fn get_live_bookmarks() -> ExternResult<Vec<(ActionHash, Timestamp, Bookmark)>> {
+    let deleted_hashes: BTreeSet<ActionHash> =
+        query(ChainQueryFilter::new().action_type(ActionType::Delete))?
+            .into_iter()
+            .filter_map(|record| match record.action() {
+                Action::Delete(delete) => Some(delete.deletes_address.clone()),
+                _ => None,
+            })
+            .collect();
+
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::Bookmark.try_into()?)
+            .include_entries(true),
+    )?;
+
+    let mut bookmarks: Vec<(ActionHash, Timestamp, Bookmark)> = vec![];
+
+    for record in records {
+        if deleted_hashes.contains(record.action_address()) {
+            continue;
+        }
+
+        let bookmark: Bookmark = record_to_entry(&record)?;
+
+        bookmarks.push((
+            record.action_address().clone(),
+            record.action().timestamp(),
+            bookmark,
+        ));
+    }
+
+    Ok(bookmarks)
+}
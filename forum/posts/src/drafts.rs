@@ -0,0 +1,122 @@
+use hdk::prelude::*;
+use std::collections::BTreeMap;
+use zome_utils::{record_to_entry, ZomeError};
+
+use crate::{create_post, CreatePostInput, EntryTypes, Post, UnitEntryTypes};
+
+// Private post that is still being written, only visible in our own source chain
+#[hdk_entry_helper]
+pub struct Draft {
+    pub title: String,
+    pub content: String,
+    #[serde(default)]
+    pub attachments: Vec<EntryHash>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SaveDraftInput {
+    draft_to_update: Option<ActionHash>,
+    draft: Draft,
+}
+
+// Saves a new draft, or a new revision of the given one
+//
+// Only the latest revision of a draft can be updated, so that its revisions don't fork
+#[hdk_extern]
+pub fn save_draft(input: SaveDraftInput) -> ExternResult<ActionHash> {
+    let Some(draft_to_update) = input.draft_to_update else {
+        return create_entry(EntryTypes::Draft(input.draft));
+    };
+
+    let is_latest_revision = list_drafts(())?
+        .iter()
+        .any(|record| record.action_address().eq(&draft_to_update));
+
+    if !is_latest_revision {
+        return Err(ZomeError::InvalidRequest(
+            "Only the latest revision of an unpublished draft can be updated".into(),
+        )
+        .into());
+    }
+
+    update_entry(draft_to_update, &input.draft)
+}
+
+// Gets the latest revision of all the drafts that haven't been published or discarded
+#[hdk_extern]
+pub fn list_drafts(_: ()) -> ExternResult<Vec<Record>> {
+    let superseded_hashes: BTreeSet<ActionHash> = query(ChainQueryFilter::new())?
+        .into_iter()
+        .filter_map(|record| match record.action() {
+            Action::Update(update) => Some(update.original_action_address.clone()),
+            Action::Delete(delete) => Some(delete.deletes_address.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::Draft.try_into()?)
+            .include_entries(true),
+    )?;
+
+    Ok(records
+        .into_iter()
+        .filter(|record| !superseded_hashes.contains(record.action_address()))
+        .collect())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PublishDraftInput {
+    draft: ActionHash,
+    channel: String,
+}
+
+// Publishes the latest revision of the given draft in the given channel, and deletes the draft
+#[hdk_extern]
+pub fn publish_draft(input: PublishDraftInput) -> ExternResult<ActionHash> {
+    let record = get_latest_draft(input.draft)?;
+    let draft: Draft = record_to_entry(&record)?;
+
+    let action_hash = create_post(CreatePostInput {
+        post: Post {
+            title: draft.title,
+            content: draft.content,
+            attachments: draft.attachments,
+        },
+        channel: input.channel,
+    })?;
+
+    delete_entry(record.action_address().clone())?;
+
+    Ok(action_hash)
+}
+
+// Gets the latest revision of the given draft, as long as it hasn't been published or discarded
+//
+// Drafts are private so they are only found in our own source chain
+fn get_latest_draft(draft_hash: ActionHash) -> ExternResult<Record> {
+    let updates: BTreeMap<ActionHash, ActionHash> =
+        query(ChainQueryFilter::new().action_type(ActionType::Update))?
+            .into_iter()
+            .filter_map(|record| match record.action() {
+                Action::Update(update) => Some((
+                    update.original_action_address.clone(),
+                    record.action_address().clone(),
+                )),
+                _ => None,
+            })
+            .collect();
+
+    let mut latest_hash = draft_hash;
+    while let Some(update_hash) = updates.get(&latest_hash) {
+        latest_hash = update_hash.clone();
+    }
+
+    list_drafts(())?
+        .into_iter()
+        .find(|record| record.action_address().eq(&latest_hash))
+        .ok_or(
+            ZomeError::NotFound("Draft not found, or already published or discarded".into()).into(),
+        )
+}
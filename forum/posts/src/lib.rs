@@ -1,9 +1,52 @@
 use hdk::{hash_path::path::TypedPath, prelude::*};
+use zome_utils::{get_latest_revision, ZomeError};
+
+#[cfg(not(feature = "exercise3step1"))]
+mod bookmarks;
+#[cfg(not(feature = "exercise3step1"))]
+mod drafts;
+#[cfg(not(feature = "exercise3step1"))]
+mod polls;
+#[cfg(not(feature = "exercise3step1"))]
+mod properties;
+#[cfg(not(feature = "exercise3step1"))]
+mod search;
+#[cfg(not(feature = "exercise3step1"))]
+mod signals;
+#[cfg(not(feature = "exercise3step1"))]
+mod unread;
+
+#[cfg(not(feature = "exercise3step1"))]
+use bookmarks::Bookmark;
+#[cfg(not(feature = "exercise3step1"))]
+use drafts::Draft;
+#[cfg(not(feature = "exercise3step1"))]
+use polls::{validate_create_link_vote, validate_create_poll, Poll};
+#[cfg(not(feature = "exercise3step1"))]
+use properties::Properties;
+#[cfg(not(feature = "exercise3step1"))]
+use search::{
+    get_original_post_hash, index_post, reindex_post, validate_create_link_term,
+    validate_delete_link_term,
+};
+#[cfg(not(feature = "exercise3step1"))]
+use unread::ChannelRead;
+#[cfg(not(feature = "exercise3step1"))]
+use zome_utils::{
+    create_mention_links, dna_properties, record_to_entry, validate_attachments,
+    validate_create_link_mention, validate_create_link_subscriber, validate_delete_link_subscriber,
+    validate_rate_limit, Mention,
+};
 
 #[hdk_entry_helper]
+#[derive(Clone)]
 pub struct Post {
     pub title: String,
     pub content: String,
+    // Manifest hashes of the files in the files zome
+    #[cfg(not(feature = "exercise3step1"))]
+    #[serde(default)]
+    pub attachments: Vec<EntryHash>,
 }
 
 #[hdk_link_types]
@@ -11,6 +54,11 @@ pub struct Post {
 pub enum LinkTypes {
     PathToChannel,
     ChannelToPost,
+    MentionToAgent,
+    ChannelToSubscriber,
+    TermToPost,
+    ChannelToPoll,
+    PollToVote,
 }
 
 #[hdk_entry_defs]
@@ -19,6 +67,14 @@ pub enum LinkTypes {
 pub enum EntryTypes {
     #[entry_def(name = "post")]
     Post(Post),
+    #[entry_def(name = "channel_read", visibility = "private")]
+    ChannelRead(ChannelRead),
+    #[entry_def(name = "bookmark", visibility = "private")]
+    Bookmark(Bookmark),
+    #[entry_def(name = "draft", visibility = "private")]
+    Draft(Draft),
+    #[entry_def(name = "poll")]
+    Poll(Poll),
 }
 
 #[cfg(not(feature = "exercise3step4"))]
@@ -45,7 +101,8 @@ pub struct CreatePostInput {
 #[hdk_extern]
 #[cfg(not(feature = "exercise3step1"))]
 pub fn create_post(input: CreatePostInput) -> ExternResult<ActionHash> {
-    let action_hash = create_entry(EntryTypes::Post(input.post))?;
+    let post = input.post;
+    let action_hash = create_entry(EntryTypes::Post(post.clone()))?;
 
     let path = channel_path(input.channel)?;
     path.ensure()?;
@@ -57,9 +114,31 @@ pub fn create_post(input: CreatePostInput) -> ExternResult<ActionHash> {
         (),
     )?;
 
+    create_mention_links(
+        &mentionable_text(&post),
+        None,
+        action_hash.clone(),
+        LinkTypes::MentionToAgent,
+    )?;
+
+    index_post(&post, action_hash.clone(), action_hash.clone())?;
+
     Ok(action_hash)
 }
 
+// The text of a post in which agents can be mentioned
+#[cfg(not(feature = "exercise3step1"))]
+fn mentionable_text(post: &Post) -> String {
+    format!("{}\n{}", post.title, post.content)
+}
+
+// Gets the posts that have mentioned us since the given time, newest first
+#[hdk_extern]
+#[cfg(not(feature = "exercise3step1"))]
+pub fn get_my_mentions(since: Timestamp) -> ExternResult<Vec<Mention>> {
+    zome_utils::get_my_mentions(since, LinkTypes::MentionToAgent)
+}
+
 // Get the header hashes for all the posts that have been created
 #[hdk_extern]
 #[cfg(not(feature = "exercise3step4"))]
@@ -90,7 +169,7 @@ pub fn get_all_channels(_: ()) -> ExternResult<Vec<String>> {
         .filter_map(|p| p.leaf().cloned())
         .map(|c| String::try_from(&c))
         .collect::<Result<Vec<String>, SerializedBytesError>>()
-        .map_err(|err| wasm_error!(err))?;
+        .map_err(|err| ZomeError::Malformed(format!("Malformed channel name: {:?}", err)))?;
 
     Ok(channels)
 }
@@ -102,10 +181,36 @@ pub struct UpdatePostInput {
 }
 
 // Updates the original_action_hash post with the given contents
+//
+// The agents mentioned for the first time in the new revision are linked to it
 #[hdk_extern]
 #[cfg(not(feature = "exercise3step8"))]
 pub fn update_post(input: UpdatePostInput) -> ExternResult<ActionHash> {
-    update_entry(input.post_to_update, &input.updated_post)
+    #[cfg(not(feature = "exercise3step1"))]
+    let original_post_hash = get_original_post_hash(input.post_to_update.clone())?;
+    #[cfg(not(feature = "exercise3step1"))]
+    let previous_post: Post = record_to_entry(&get_latest_post(original_post_hash.clone())?)?;
+
+    let action_hash = update_entry(input.post_to_update, &input.updated_post)?;
+
+    #[cfg(not(feature = "exercise3step1"))]
+    {
+        reindex_post(
+            original_post_hash,
+            &previous_post,
+            &input.updated_post,
+            action_hash.clone(),
+        )?;
+
+        create_mention_links(
+            &mentionable_text(&input.updated_post),
+            Some(&mentionable_text(&previous_post)),
+            action_hash.clone(),
+            LinkTypes::MentionToAgent,
+        )?;
+    }
+
+    Ok(action_hash)
 }
 
 // Get the latest post content from its original header hash
@@ -118,16 +223,99 @@ pub fn get_post(action_hash: ActionHash) -> ExternResult<Record> {
 }
 
 fn get_latest_post(action_hash: ActionHash) -> ExternResult<Record> {
-    let details = get_details(action_hash, GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Post not found".into())))?;
-
-    match details {
-        Details::Entry(_) => Err(wasm_error!(WasmErrorInner::Guest(
-            "Malformed details".into()
-        ))),
-        Details::Record(element_details) => match element_details.updates.last() {
-            Some(update) => get_latest_post(update.action_address().clone()),
-            None => Ok(element_details.record),
+    get_latest_revision(action_hash)?.ok_or(ZomeError::NotFound("Post not found".into()).into())
+}
+
+#[hdk_extern]
+#[cfg(not(feature = "exercise3step1"))]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    match op.to_type::<EntryTypes, LinkTypes>()? {
+        OpType::RegisterAgentActivity(OpActivity::CreateEntry {
+            app_entry_type: Some(UnitEntryTypes::Post),
+            action,
+        }) => match dna_properties::<Properties>()?.post_rate_limit {
+            Some(rate_limit) => validate_rate_limit(&action, &rate_limit),
+            None => Ok(ValidateCallbackResult::Valid),
         },
+        OpType::StoreEntry(OpEntry::CreateEntry {
+            app_entry: EntryTypes::Post(post),
+            ..
+        })
+        | OpType::StoreEntry(OpEntry::UpdateEntry {
+            app_entry: EntryTypes::Post(post),
+            ..
+        }) => validate_attachments(&post.attachments),
+        OpType::StoreEntry(OpEntry::CreateEntry {
+            app_entry: EntryTypes::Poll(poll),
+            action,
+        }) => validate_create_poll(&action, poll),
+        OpType::RegisterUpdate(OpUpdate::Entry {
+            app_entry: EntryTypes::Poll(_),
+            ..
+        }) => Ok(ValidateCallbackResult::Invalid(
+            "Polls cannot be updated".into(),
+        )),
+        OpType::RegisterCreateLink {
+            link_type: LinkTypes::MentionToAgent,
+            target_address,
+            action,
+            ..
+        } => validate_create_link_mention(&action, target_address, is_post),
+        OpType::RegisterCreateLink {
+            link_type: LinkTypes::ChannelToSubscriber,
+            target_address,
+            action,
+            ..
+        } => validate_create_link_subscriber(action, target_address),
+        OpType::RegisterDeleteLink {
+            link_type: LinkTypes::ChannelToSubscriber,
+            original_action,
+            action,
+            ..
+        } => validate_delete_link_subscriber(action, original_action),
+        OpType::RegisterCreateLink {
+            link_type: LinkTypes::TermToPost,
+            base_address,
+            target_address,
+            tag,
+            action,
+        } => validate_create_link_term(action, base_address, target_address, tag),
+        OpType::RegisterDeleteLink {
+            link_type: LinkTypes::TermToPost,
+            original_action,
+            action,
+            ..
+        } => validate_delete_link_term(action, original_action),
+        OpType::RegisterCreateLink {
+            link_type: LinkTypes::PollToVote,
+            base_address,
+            target_address,
+            tag,
+            action,
+        } => validate_create_link_vote(action, base_address, target_address, tag),
+        OpType::RegisterDeleteLink {
+            link_type: LinkTypes::PollToVote,
+            ..
+        } => Ok(ValidateCallbackResult::Invalid(
+            "Votes cannot be withdrawn".into(),
+        )),
+        _ => Ok(ValidateCallbackResult::Valid),
     }
 }
+
+#[cfg(not(feature = "exercise3step1"))]
+fn is_post(record: &Record) -> ExternResult<bool> {
+    let (Some(EntryType::App(app_entry_def)), Some(entry)) =
+        (record.action().entry_type(), record.entry().as_option())
+    else {
+        return Ok(false);
+    };
+
+    let app_entry = EntryTypes::deserialize_from_type(
+        app_entry_def.zome_index,
+        app_entry_def.entry_index,
+        entry,
+    )?;
+
+    Ok(matches!(app_entry, Some(EntryTypes::Post(_))))
+}
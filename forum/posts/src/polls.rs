@@ -0,0 +1,226 @@
+use hdk::prelude::*;
+use zome_utils::{
+    get_typed_entry, links_to_records, record_to_entry, walk_source_chain, ZomeError,
+};
+
+use crate::{channel_path, EntryTypes, LinkTypes};
+
+#[hdk_entry_helper]
+pub struct Poll {
+    pub question: String,
+    pub options: Vec<String>,
+    pub closes_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreatePollInput {
+    poll: Poll,
+    channel: String,
+}
+
+// Creates the given poll in the given channel
+#[hdk_extern]
+pub fn create_poll(input: CreatePollInput) -> ExternResult<ActionHash> {
+    let action_hash = create_entry(EntryTypes::Poll(input.poll))?;
+
+    let path = channel_path(input.channel)?;
+    path.ensure()?;
+
+    create_link(
+        path.path_entry_hash()?,
+        action_hash.clone(),
+        LinkTypes::ChannelToPoll,
+        (),
+    )?;
+
+    Ok(action_hash)
+}
+
+// Gets all the polls in the given channel, the newest first
+#[hdk_extern]
+pub fn get_channel_polls(channel: String) -> ExternResult<Vec<Record>> {
+    let path = channel_path(channel)?;
+
+    let mut links = get_links(path.path_entry_hash()?, LinkTypes::ChannelToPoll, None)?;
+
+    links.sort_by_key(|link| std::cmp::Reverse(link.timestamp));
+
+    links_to_records(links, GetOptions::default())
+}
+
+// The chosen option is kept in the tag of the link from the poll to the voter
+fn option_tag(option: u32) -> LinkTag {
+    LinkTag::new(option.to_le_bytes().to_vec())
+}
+
+fn tag_option(tag: &LinkTag) -> Option<u32> {
+    tag.0.as_slice().try_into().ok().map(u32::from_le_bytes)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VoteInput {
+    poll: ActionHash,
+    option: u32,
+}
+
+// Votes for the option with the given index in the given poll
+#[hdk_extern]
+pub fn vote(input: VoteInput) -> ExternResult<ActionHash> {
+    create_link(
+        input.poll,
+        agent_info()?.agent_initial_pubkey,
+        LinkTypes::PollToVote,
+        option_tag(input.option),
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PollResults {
+    pub poll: Poll,
+    // Number of votes for each of the options of the poll, in the same order
+    pub votes: Vec<u32>,
+    pub total_votes: u32,
+    pub closed: bool,
+}
+
+// Counts the votes that the given poll has received
+#[hdk_extern]
+pub fn get_poll_results(poll_hash: ActionHash) -> ExternResult<PollResults> {
+    let poll = get_poll(poll_hash.clone())?;
+
+    let links = get_links(poll_hash, LinkTypes::PollToVote, None)?;
+
+    let mut votes = vec![0; poll.options.len()];
+    let mut total_votes = 0;
+
+    for link in links {
+        if let Some(option_votes) =
+            tag_option(&link.tag).and_then(|option| votes.get_mut(option as usize))
+        {
+            *option_votes += 1;
+            total_votes += 1;
+        }
+    }
+
+    let closed = sys_time()? >= poll.closes_at;
+
+    Ok(PollResults {
+        poll,
+        votes,
+        total_votes,
+        closed,
+    })
+}
+
+fn get_poll(poll_hash: ActionHash) -> ExternResult<Poll> {
+    get_typed_entry(poll_hash, GetOptions::default())?
+        .ok_or(ZomeError::NotFound("Poll not found".into()).into())
+}
+
+pub fn validate_create_poll(action: &Create, poll: Poll) -> ExternResult<ValidateCallbackResult> {
+    if poll.question.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A poll must have a question".into(),
+        ));
+    }
+
+    if poll.options.len() < 2 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A poll must have at least two options".into(),
+        ));
+    }
+
+    if poll.closes_at <= action.timestamp {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A poll must close after it is created".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Agents can only vote for themselves, once, for one of the options and before the poll closes
+pub fn validate_create_link_vote(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if AnyLinkableHash::from(action.author.clone()).ne(&target_address) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Agents can only vote for themselves".into(),
+        ));
+    }
+
+    let record = must_get_valid_record(ActionHash::from(base_address.clone()))?;
+    let maybe_poll: Option<Poll> = record_to_entry(&record).ok();
+
+    let poll = match maybe_poll {
+        Some(poll) => poll,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Votes can only be cast on polls".into(),
+            ))
+        }
+    };
+
+    if action.timestamp < record.action().timestamp() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Votes can't be cast before the poll was created".into(),
+        ));
+    }
+
+    if action.timestamp >= poll.closes_at {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The poll is already closed".into(),
+        ));
+    }
+
+    match tag_option(&tag) {
+        Some(option) if (option as usize) < poll.options.len() => {}
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "The vote must be for one of the options of the poll".into(),
+            ))
+        }
+    }
+
+    validate_first_vote(&action, &base_address, record.action().timestamp())
+}
+
+// Walks the voter's source chain looking for an earlier vote on the same poll
+//
+// The walk stops at the creation of the poll, since no vote on it can be older than that
+fn validate_first_vote(
+    action: &CreateLink,
+    poll_address: &AnyLinkableHash,
+    poll_created_at: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
+    let already_voted = walk_source_chain(
+        &action.author,
+        action.prev_action.clone(),
+        |previous_action| {
+            if previous_action.timestamp() < poll_created_at {
+                return Some(false);
+            }
+
+            match previous_action {
+                Action::CreateLink(create_link)
+                    if create_link.zome_index == action.zome_index
+                        && create_link.link_type == action.link_type
+                        && create_link.base_address.eq(poll_address) =>
+                {
+                    Some(true)
+                }
+                _ => None,
+            }
+        },
+    )?;
+
+    match already_voted {
+        Some(true) => Ok(ValidateCallbackResult::Invalid(
+            "Agents can only vote once on each poll".into(),
+        )),
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
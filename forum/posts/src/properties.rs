@@ -0,0 +1,8 @@
+use hdk::prelude::*;
+use zome_utils::RateLimit;
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Properties {
+    #[serde(default)]
+    pub post_rate_limit: Option<RateLimit>,
+}
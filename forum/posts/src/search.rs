@@ -0,0 +1,260 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use hdk::prelude::*;
+use zome_utils::{get_links_batch, record_to_entry, ZomeError};
+
+use crate::{channel_path, is_post, LinkTypes, Post};
+
+// Terms shorter than this are too common to be worth indexing
+const MIN_TERM_LENGTH: usize = 2;
+const MAX_TERM_LENGTH: usize = 64;
+
+// Splits the given text into lowercase words, counting how many times each of them appears
+pub fn tokenize(text: &str) -> BTreeMap<String, u32> {
+    let mut terms: BTreeMap<String, u32> = BTreeMap::new();
+
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        let length = word.chars().count();
+
+        if (MIN_TERM_LENGTH..=MAX_TERM_LENGTH).contains(&length) {
+            *terms.entry(word.to_lowercase()).or_insert(0) += 1;
+        }
+    }
+
+    terms
+}
+
+fn post_terms(post: &Post) -> BTreeMap<String, u32> {
+    tokenize(&format!("{} {}", post.title, post.content))
+}
+
+fn term_path(term: &str) -> Path {
+    let mut path = Path::from("search_index");
+    path.append_component(term.into());
+
+    path
+}
+
+// The tag of a term link keeps the revision of the post the term was found in, for validators
+// to check it, and the number of times it appears there, to rank the results
+#[derive(Serialize, Deserialize, Debug)]
+struct TermTag {
+    revision: ActionHash,
+    occurrences: u32,
+}
+
+fn term_tag(revision: ActionHash, occurrences: u32) -> ExternResult<LinkTag> {
+    let bytes = encode(&TermTag {
+        revision,
+        occurrences,
+    })
+    .map_err(|err| ZomeError::Malformed(format!("Malformed term tag: {:?}", err)))?;
+
+    Ok(LinkTag::new(bytes))
+}
+
+fn tag_occurrences(tag: &LinkTag) -> u32 {
+    decode::<_, TermTag>(&tag.0)
+        .map(|term_tag| term_tag.occurrences)
+        .unwrap_or(1)
+}
+
+// Links every term of the given revision of a post to the original post
+pub fn index_post(
+    post: &Post,
+    original_post_hash: ActionHash,
+    revision_hash: ActionHash,
+) -> ExternResult<()> {
+    for (term, occurrences) in post_terms(post) {
+        create_link(
+            term_path(&term).path_entry_hash()?,
+            original_post_hash.clone(),
+            LinkTypes::TermToPost,
+            term_tag(revision_hash.clone(), occurrences)?,
+        )?;
+    }
+
+    Ok(())
+}
+
+// Replaces the terms indexed for the previous revision of the given post with the ones of its new revision
+pub fn reindex_post(
+    original_post_hash: ActionHash,
+    previous_post: &Post,
+    updated_post: &Post,
+    revision_hash: ActionHash,
+) -> ExternResult<()> {
+    let bases = post_terms(previous_post)
+        .into_keys()
+        .map(|term| term_path(&term).path_entry_hash())
+        .collect::<ExternResult<Vec<EntryHash>>>()?;
+
+    let post_target = AnyLinkableHash::from(original_post_hash.clone());
+
+    for links in get_links_batch(bases, LinkTypes::TermToPost)? {
+        for link in links {
+            if link.target == post_target {
+                delete_link(link.create_link_hash)?;
+            }
+        }
+    }
+
+    index_post(updated_post, original_post_hash, revision_hash)
+}
+
+// Gets the original action hash for the given revision of a post
+pub fn get_original_post_hash(post_hash: ActionHash) -> ExternResult<ActionHash> {
+    let record = get(post_hash.clone(), GetOptions::default())?
+        .ok_or(ZomeError::NotFound("Post not found".into()))?;
+
+    match record.action() {
+        Action::Update(update) => get_original_post_hash(update.original_action_address.clone()),
+        _ => Ok(post_hash),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchPostsInput {
+    query: String,
+    channel: Option<String>,
+}
+
+// Gets the posts that contain all the words in the query, the ones with the most matches first
+#[hdk_extern]
+pub fn search_posts(input: SearchPostsInput) -> ExternResult<Vec<ActionHash>> {
+    let terms: Vec<String> = tokenize(&input.query).into_keys().collect();
+
+    if terms.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let bases = terms
+        .iter()
+        .map(|term| term_path(term).path_entry_hash())
+        .collect::<ExternResult<Vec<EntryHash>>>()?;
+
+    // Post hash -> (matches, latest timestamp)
+    let mut results: Option<BTreeMap<ActionHash, (u32, Timestamp)>> = None;
+
+    for links in get_links_batch(bases, LinkTypes::TermToPost)? {
+        let mut term_results: BTreeMap<ActionHash, (u32, Timestamp)> = BTreeMap::new();
+
+        for link in links {
+            let (matches, timestamp) = term_results
+                .entry(ActionHash::from(link.target))
+                .or_insert((0, link.timestamp));
+
+            *matches = (*matches).max(tag_occurrences(&link.tag));
+            *timestamp = (*timestamp).max(link.timestamp);
+        }
+
+        results = Some(match results {
+            None => term_results,
+            Some(results) => results
+                .into_iter()
+                .filter_map(|(post_hash, (matches, timestamp))| {
+                    term_results
+                        .get(&post_hash)
+                        .map(|(term_matches, _)| (post_hash, (matches + term_matches, timestamp)))
+                })
+                .collect(),
+        });
+    }
+
+    let mut results: Vec<(ActionHash, (u32, Timestamp))> =
+        results.unwrap_or_default().into_iter().collect();
+
+    if let Some(channel) = input.channel {
+        let channel_posts: BTreeSet<ActionHash> = get_links(
+            channel_path(channel)?.path_entry_hash()?,
+            LinkTypes::ChannelToPost,
+            None,
+        )?
+        .into_iter()
+        .map(|link| ActionHash::from(link.target))
+        .collect();
+
+        results.retain(|(post_hash, _)| channel_posts.contains(post_hash));
+    }
+
+    results.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    Ok(results
+        .into_iter()
+        .map(|(post_hash, _)| post_hash)
+        .collect())
+}
+
+// Only the author of a post can index it, and only under the terms found in the revision of the
+// post given in the tag
+pub fn validate_create_link_term(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(post_hash) = target_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Terms can only be linked to the action hash of a post".into(),
+        ));
+    };
+
+    let Ok(term_tag) = decode::<_, TermTag>(&tag.0) else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Malformed term link tag".into(),
+        ));
+    };
+
+    let revision = must_get_valid_record(term_tag.revision)?;
+
+    if revision.action().author().ne(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a post can index it",
+        )));
+    }
+
+    if !is_post(&revision)? || must_get_original_post_hash(&revision)?.ne(&post_hash) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Terms can only be indexed from a revision of the post they are linked to".into(),
+        ));
+    }
+
+    let post: Post = record_to_entry(&revision)?;
+
+    for (term, occurrences) in post_terms(&post) {
+        if AnyLinkableHash::from(term_path(&term).path_entry_hash()?).eq(&base_address) {
+            return match occurrences == term_tag.occurrences {
+                true => Ok(ValidateCallbackResult::Valid),
+                false => Ok(ValidateCallbackResult::Invalid(
+                    "The occurrences in the tag must match the ones in the post".into(),
+                )),
+            };
+        }
+    }
+
+    Ok(ValidateCallbackResult::Invalid(
+        "Only the terms that appear in a post can be linked to it".into(),
+    ))
+}
+
+// Follows the given revision of a post back to its original action, getting only valid records
+fn must_get_original_post_hash(record: &Record) -> ExternResult<ActionHash> {
+    match record.action() {
+        Action::Update(update) => must_get_original_post_hash(&must_get_valid_record(
+            update.original_action_address.clone(),
+        )?),
+        _ => Ok(record.action_address().clone()),
+    }
+}
+
+pub fn validate_delete_link_term(
+    action: DeleteLink,
+    original_action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+    match original_action.author.eq(&action.author) {
+        true => Ok(ValidateCallbackResult::Valid),
+        false => Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the author of a post can remove it from the index",
+        ))),
+    }
+}
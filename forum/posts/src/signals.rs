@@ -0,0 +1,79 @@
+use hdk::prelude::*;
+use zome_utils::{grant_unrestricted_access, signal_subscribers, unsubscribe};
+
+use crate::{channel_path, LinkTypes};
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum Signal {
+    NewPost { post_hash: ActionHash },
+}
+
+// Allows any agent to send us signals
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    grant_unrestricted_access("recv_remote_signal")?;
+
+    Ok(InitCallbackResult::Pass)
+}
+
+// Starts receiving signals for the new posts in the given channel
+#[hdk_extern]
+pub fn subscribe_to_channel(channel: String) -> ExternResult<()> {
+    create_link(
+        channel_path(channel)?.path_entry_hash()?,
+        agent_info()?.agent_initial_pubkey,
+        LinkTypes::ChannelToSubscriber,
+        (),
+    )?;
+
+    Ok(())
+}
+
+// Stops receiving signals for the new posts in the given channel
+#[hdk_extern]
+pub fn unsubscribe_from_channel(channel: String) -> ExternResult<()> {
+    unsubscribe(
+        channel_path(channel)?.path_entry_hash()?,
+        LinkTypes::ChannelToSubscriber,
+    )
+}
+
+#[hdk_extern]
+pub fn recv_remote_signal(signal: Signal) -> ExternResult<()> {
+    emit_signal(signal)
+}
+
+#[hdk_extern(infallible)]
+pub fn post_commit(committed_actions: Vec<SignedActionHashed>) {
+    if let Err(err) = signal_new_posts(committed_actions) {
+        error!("Error signaling the new posts: {:?}", err);
+    }
+}
+
+// Signals every new post to ourselves and to the subscribers of its channel
+fn signal_new_posts(committed_actions: Vec<SignedActionHashed>) -> ExternResult<()> {
+    for action in committed_actions {
+        let Action::CreateLink(create_link) = action.action() else {
+            continue;
+        };
+
+        let Some(LinkTypes::ChannelToPost) =
+            LinkTypes::from_type(create_link.zome_index, create_link.link_type)?
+        else {
+            continue;
+        };
+
+        let signal = Signal::NewPost {
+            post_hash: ActionHash::from(create_link.target_address.clone()),
+        };
+
+        signal_subscribers(
+            &signal,
+            create_link.base_address.clone(),
+            LinkTypes::ChannelToSubscriber,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use hdk::prelude::*;
+use std::collections::BTreeMap;
+use zome_utils::{get_links_batch, record_to_entry};
+
+use crate::{channel_path, get_all_channels, EntryTypes, LinkTypes, UnitEntryTypes};
+
+// Private record of the last time we read a channel
+#[hdk_entry_helper]
+pub struct ChannelRead {
+    pub channel: String,
+    pub read_at: Timestamp,
+}
+
+// Records that we have read all the posts in the given channel until now
+#[hdk_extern]
+pub fn mark_read(channel: String) -> ExternResult<()> {
+    create_entry(EntryTypes::ChannelRead(ChannelRead {
+        channel,
+        read_at: sys_time()?,
+    }))?;
+
+    Ok(())
+}
+
+// Gets the number of posts that others have made in each channel since we last read it
+#[hdk_extern]
+pub fn get_unread_counts(_: ()) -> ExternResult<BTreeMap<String, usize>> {
+    let last_reads = get_last_reads()?;
+    let my_pub_key = agent_info()?.agent_initial_pubkey;
+
+    let channels = get_all_channels(())?;
+    let channel_hashes = channels
+        .iter()
+        .map(|channel| channel_path(channel.clone())?.path_entry_hash())
+        .collect::<ExternResult<Vec<EntryHash>>>()?;
+
+    let channels_links = get_links_batch(channel_hashes, LinkTypes::ChannelToPost)?;
+
+    let mut unread_counts: BTreeMap<String, usize> = BTreeMap::new();
+
+    for (channel, links) in channels.into_iter().zip(channels_links) {
+        let last_read = last_reads.get(&channel);
+
+        let unread_count = links
+            .into_iter()
+            .filter(|link| link.author.ne(&my_pub_key))
+            .filter(|link| match last_read {
+                Some(read_at) => link.timestamp.gt(read_at),
+                None => true,
+            })
+            .count();
+
+        unread_counts.insert(channel, unread_count);
+    }
+
+    Ok(unread_counts)
+}
+
+fn get_last_reads() -> ExternResult<BTreeMap<String, Timestamp>> {
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::ChannelRead.try_into()?)
+            .include_entries(true),
+    )?;
+
+    let mut last_reads: BTreeMap<String, Timestamp> = BTreeMap::new();
+
+    for record in records {
+        let channel_read: ChannelRead = record_to_entry(&record)?;
+
+        let last_read = last_reads
+            .entry(channel_read.channel)
+            .or_insert(channel_read.read_at);
+
+        if channel_read.read_at.gt(last_read) {
+            *last_read = channel_read.read_at;
+        }
+    }
+
+    Ok(last_reads)
+}
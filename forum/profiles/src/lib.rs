@@ -1,4 +1,7 @@
 use hdk::prelude::*;
+use zome_utils::get_typed_entry;
+#[cfg(not(feature = "exercise1step1"))]
+use zome_utils::{record_to_entry, ZomeError};
 
 #[hdk_entry_helper]
 pub struct Profile {
@@ -17,26 +20,114 @@ pub enum EntryTypes {
 #[cfg(not(feature = "exercise1step3"))]
 pub enum LinkTypes {
     AgentToProfile,
+    NicknameToAgent,
+    ProfileToEncryptionKey,
+}
+
+// Nicknames are matched without regard to case
+#[cfg(not(feature = "exercise1step1"))]
+fn nickname_path(nickname: String) -> Path {
+    let mut path = Path::from("nicknames");
+    path.append_component(nickname.to_lowercase().into());
+
+    path
 }
 
 // Create the given profile and associates it with our public key
 #[hdk_extern]
 #[cfg(not(feature = "exercise1step1"))]
 pub fn create_profile(profile: Profile) -> ExternResult<ActionHash> {
+    let nickname = profile.nickname.clone();
     let action_hash = create_entry(EntryTypes::Profile(profile))?;
 
     let my_pub_key = agent_info()?.agent_initial_pubkey;
 
     create_link(
-        my_pub_key,
+        my_pub_key.clone(),
         action_hash.clone(),
         LinkTypes::AgentToProfile,
         (),
     )?;
 
+    // The profile goes in the tag so that validators can check the nickname
+    create_link(
+        nickname_path(nickname).path_entry_hash()?,
+        my_pub_key,
+        LinkTypes::NicknameToAgent,
+        LinkTag::new(encode(&action_hash).map_err(|err| {
+            ZomeError::Internal(format!("Could not encode the profile hash: {:?}", err))
+        })?),
+    )?;
+
+    publish_encryption_key(())?;
+
     Ok(action_hash)
 }
 
+// Creates a new X25519 keypair in our keystore and links its public key from our profile,
+// so that other agents can encrypt data that only we can decrypt
+#[hdk_extern]
+#[cfg(not(feature = "exercise1step1"))]
+pub fn publish_encryption_key(_: ()) -> ExternResult<X25519PubKey> {
+    let my_pub_key = agent_info()?.agent_initial_pubkey;
+
+    let profile_hash = get_agent_profile_hash(my_pub_key.clone())?.ok_or(
+        ZomeError::InvalidRequest("A profile is needed to publish an encryption key".into()),
+    )?;
+
+    let key = create_x25519_keypair()?;
+
+    // The key is small enough to live in the tag of the link
+    create_link(
+        profile_hash,
+        my_pub_key,
+        LinkTypes::ProfileToEncryptionKey,
+        LinkTag::new(key.as_ref().to_vec()),
+    )?;
+
+    Ok(key)
+}
+
+// Gets the latest encryption key linked from the profile of the given agent, if they have published one
+#[hdk_extern]
+#[cfg(not(feature = "exercise1step1"))]
+pub fn get_agent_encryption_key(agent_pub_key: AgentPubKey) -> ExternResult<Option<X25519PubKey>> {
+    let Some(profile_hash) = get_agent_profile_hash(agent_pub_key)? else {
+        return Ok(None);
+    };
+
+    let links = get_links(profile_hash, LinkTypes::ProfileToEncryptionKey, None)?;
+
+    Ok(links
+        .into_iter()
+        .max_by_key(|link| link.timestamp)
+        .and_then(|link| X25519PubKey::try_from(link.tag.0.as_slice()).ok()))
+}
+
+// Gets the agent that created a profile with the given nickname, if there is exactly one
+//
+// Nicknames claimed by more than one agent are ambiguous, since link timestamps are set by their
+// authors and can't tell which claim came first, so they don't resolve to any agent
+#[hdk_extern]
+#[cfg(not(feature = "exercise1step1"))]
+pub fn get_agent_for_nickname(nickname: String) -> ExternResult<Option<AgentPubKey>> {
+    let links = get_links(
+        nickname_path(nickname).path_entry_hash()?,
+        LinkTypes::NicknameToAgent,
+        None,
+    )?;
+
+    let agents: BTreeSet<AgentPubKey> = links
+        .into_iter()
+        .map(|link| AgentPubKey::from(EntryHash::from(link.target)))
+        .collect();
+
+    match agents.len() {
+        1 => Ok(agents.into_iter().next()),
+        _ => Ok(None),
+    }
+}
+
 // Gets the profile for the given agent, if they have created it
 #[hdk_extern]
 #[cfg(not(feature = "exercise1step4"))]
@@ -54,33 +145,132 @@ pub fn get_my_profile(_: ()) -> ExternResult<Option<Profile>> {
 }
 
 fn inner_get_agent_profile(agent_pub_key: AgentPubKey) -> ExternResult<Option<Profile>> {
+    match get_agent_profile_hash(agent_pub_key)? {
+        Some(profile_hash) => get_profile(profile_hash),
+        None => Ok(None),
+    }
+}
+
+fn get_agent_profile_hash(agent_pub_key: AgentPubKey) -> ExternResult<Option<ActionHash>> {
     let links = get_links(
         agent_pub_key,
         LinkTypeFilter::single_type(zome_info()?.id, LinkType::new(0)),
         None,
     )?;
 
-    match links.first() {
-        Some(link) => get_profile(link.target.clone().into()),
-        None => Ok(None),
-    }
+    Ok(links.first().map(|link| link.target.clone().into()))
 }
 
 fn get_profile(action_hash: ActionHash) -> ExternResult<Option<Profile>> {
-    let maybe_record = get(action_hash, GetOptions::default())?;
+    get_typed_entry(action_hash, GetOptions::default())
+}
 
-    match maybe_record {
-        None => Ok(None),
-        Some(record) => {
-            let maybe_entry: Option<Entry> = record.entry.into_option();
+#[hdk_extern]
+#[cfg(not(feature = "exercise1step1"))]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    match op.to_type::<EntryTypes, LinkTypes>()? {
+        OpType::RegisterCreateLink {
+            link_type: LinkTypes::NicknameToAgent,
+            base_address,
+            target_address,
+            action,
+            ..
+        } => validate_create_link_nickname(action, base_address, target_address),
+        OpType::RegisterDeleteLink {
+            link_type: LinkTypes::NicknameToAgent,
+            original_action,
+            action,
+            ..
+        } => match original_action.author.eq(&action.author) {
+            true => Ok(ValidateCallbackResult::Valid),
+            false => Ok(ValidateCallbackResult::Invalid(String::from(
+                "Only the agent that claimed a nickname can release it",
+            ))),
+        },
+        OpType::RegisterCreateLink {
+            link_type: LinkTypes::ProfileToEncryptionKey,
+            base_address,
+            target_address,
+            action,
+            ..
+        } => validate_create_link_encryption_key(action, base_address, target_address),
+        OpType::RegisterDeleteLink {
+            link_type: LinkTypes::ProfileToEncryptionKey,
+            ..
+        } => Ok(ValidateCallbackResult::Invalid(String::from(
+            "Encryption keys cannot be unpublished",
+        ))),
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+// Agents can only claim the nickname in their own profile, for themselves
+#[cfg(not(feature = "exercise1step1"))]
+fn validate_create_link_nickname(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    if AnyLinkableHash::from(action.author.clone()).ne(&target_address) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Agents can only claim nicknames for themselves",
+        )));
+    }
+
+    let Ok(profile_hash) = decode::<_, ActionHash>(&action.tag.0) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Nickname links must carry the profile they were claimed from",
+        )));
+    };
+    let profile_record = must_get_valid_record(profile_hash)?;
+
+    if profile_record.action().author().ne(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Agents can only claim the nickname in their own profile",
+        )));
+    }
+
+    let Ok(profile) = record_to_entry::<Profile>(&profile_record) else {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Nickname links must be claimed from a profile",
+        )));
+    };
 
-            let entry: Entry = maybe_entry.ok_or(wasm_error!(WasmErrorInner::Guest(
-                String::from("This record doesn't include any entry")
-            )))?;
+    let nickname_hash = nickname_path(profile.nickname).path_entry_hash()?;
 
-            let profile = Profile::try_from(entry)?;
+    match AnyLinkableHash::from(nickname_hash).eq(&base_address) {
+        true => Ok(ValidateCallbackResult::Valid),
+        false => Ok(ValidateCallbackResult::Invalid(String::from(
+            "Agents can only claim the nickname in their own profile",
+        ))),
+    }
+}
+
+// Agents can only publish encryption keys from their own profile
+#[cfg(not(feature = "exercise1step1"))]
+fn validate_create_link_encryption_key(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    if AnyLinkableHash::from(action.author.clone()).ne(&target_address) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Encryption keys must point to the agent that published them",
+        )));
+    }
+
+    let profile_record = must_get_valid_record(ActionHash::from(base_address))?;
+
+    if profile_record.action().author().ne(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(String::from(
+            "Agents can only publish encryption keys from their own profile",
+        )));
+    }
 
-            return Ok(Some(profile));
-        }
+    match X25519PubKey::try_from(action.tag.0.as_slice()) {
+        Ok(_) => Ok(ValidateCallbackResult::Valid),
+        Err(_) => Ok(ValidateCallbackResult::Invalid(String::from(
+            "Malformed encryption key",
+        ))),
     }
 }
@@ -0,0 +1,229 @@
+use hdk::prelude::*;
+use zome_utils::{call_local_zome, is_moderator, links_to_records, record_to_entry, ZomeError};
+
+// Names of the zomes in this DNA that hold the content that can be reported
+const POSTS_ZOME_NAME: &str = "posts_zome";
+const COMMENTS_ZOME_NAME: &str = "comments_zome";
+
+#[hdk_entry_helper]
+pub struct Report {
+    pub target: ActionHash,
+    pub channel: String,
+    pub reason: String,
+}
+
+#[hdk_entry_defs]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+    #[entry_def(name = "report")]
+    Report(Report),
+}
+
+#[hdk_link_types]
+pub enum LinkTypes {
+    TargetToReport,
+    InboxToReport,
+}
+
+// The moderators' inbox for the reports made on the content of the given channel
+fn inbox_path(channel: String) -> Path {
+    let mut path = Path::from("reports");
+    path.append_component(channel.into());
+
+    path
+}
+
+// Gets the agents that were designated as moderators in the DNA properties
+#[hdk_extern]
+pub fn moderators(_: ()) -> ExternResult<Vec<AgentPubKey>> {
+    zome_utils::moderators()
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReportContentInput {
+    target: ActionHash,
+    channel: String,
+    reason: String,
+}
+
+// Flags the given post or comment, leaving the report in the moderators' inbox for its channel
+//
+// The channel must be the one the reported post or poll, or the one at the root of the reported
+// comment's thread, was posted to
+#[hdk_extern]
+pub fn report_content(input: ReportContentInput) -> ExternResult<ActionHash> {
+    let root_hash: ActionHash =
+        call_local_zome(COMMENTS_ZOME_NAME, "get_thread_root", input.target.clone())?;
+    let channel_posts: Vec<ActionHash> =
+        call_local_zome(POSTS_ZOME_NAME, "get_channel_posts", input.channel.clone())?;
+    let channel_polls: Vec<Record> =
+        call_local_zome(POSTS_ZOME_NAME, "get_channel_polls", input.channel.clone())?;
+
+    let posted_to_channel = channel_posts.contains(&root_hash)
+        || channel_polls
+            .iter()
+            .any(|poll| poll.action_address().eq(&root_hash));
+
+    if !posted_to_channel {
+        return Err(ZomeError::InvalidRequest(format!(
+            "The reported content was not posted to the channel {}",
+            input.channel
+        ))
+        .into());
+    }
+
+    let action_hash = create_entry(EntryTypes::Report(Report {
+        target: input.target.clone(),
+        channel: input.channel.clone(),
+        reason: input.reason,
+    }))?;
+
+    create_link(
+        input.target,
+        action_hash.clone(),
+        LinkTypes::TargetToReport,
+        (),
+    )?;
+
+    create_link(
+        inbox_path(input.channel).path_entry_hash()?,
+        action_hash.clone(),
+        LinkTypes::InboxToReport,
+        (),
+    )?;
+
+    Ok(action_hash)
+}
+
+// Gets all the reports on the given channel that haven't been resolved yet
+#[hdk_extern]
+pub fn get_open_reports(channel: String) -> ExternResult<Vec<Record>> {
+    if !is_moderator(&agent_info()?.agent_initial_pubkey)? {
+        return Err(ZomeError::Unauthorized("Only moderators can read the reports".into()).into());
+    }
+
+    let links = get_links(
+        inbox_path(channel).path_entry_hash()?,
+        LinkTypes::InboxToReport,
+        None,
+    )?;
+
+    links_to_records(links, GetOptions::default())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResolveReportInput {
+    report: ActionHash,
+    channel: String,
+}
+
+// Removes the given report from the moderators' inbox
+#[hdk_extern]
+pub fn resolve_report(input: ResolveReportInput) -> ExternResult<()> {
+    let links = get_links(
+        inbox_path(input.channel).path_entry_hash()?,
+        LinkTypes::InboxToReport,
+        None,
+    )?;
+
+    let report_target = AnyLinkableHash::from(input.report);
+
+    for link in links {
+        if link.target == report_target {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    match op.to_type::<EntryTypes, LinkTypes>()? {
+        OpType::StoreEntry(OpEntry::CreateEntry {
+            app_entry: EntryTypes::Report(report),
+            ..
+        }) => validate_create_report(report),
+        OpType::RegisterCreateLink {
+            link_type,
+            base_address,
+            target_address,
+            action,
+            ..
+        } => validate_create_link_report(link_type, action, base_address, target_address),
+        OpType::RegisterUpdate(OpUpdate::Entry { .. }) => Ok(ValidateCallbackResult::Invalid(
+            "Reports cannot be updated".into(),
+        )),
+        OpType::RegisterDelete(OpDelete::Entry { .. }) => Ok(ValidateCallbackResult::Invalid(
+            "Reports cannot be deleted".into(),
+        )),
+        OpType::RegisterDeleteLink {
+            link_type, action, ..
+        } => match link_type {
+            LinkTypes::InboxToReport => match is_moderator(&action.author)? {
+                true => Ok(ValidateCallbackResult::Valid),
+                false => Ok(ValidateCallbackResult::Invalid(
+                    "Only moderators can resolve reports".into(),
+                )),
+            },
+            LinkTypes::TargetToReport => Ok(ValidateCallbackResult::Invalid(
+                "Reports cannot be detached from their target".into(),
+            )),
+        },
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+fn validate_create_report(report: Report) -> ExternResult<ValidateCallbackResult> {
+    if report.reason.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A report must include a reason".into(),
+        ));
+    }
+
+    let _target = must_get_valid_record(report.target)?;
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// Reports can only be linked by their author, from their target and from the inbox of their channel
+fn validate_create_link_report(
+    link_type: LinkTypes,
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(report_hash) = target_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Report links must point to the action hash of a report".into(),
+        ));
+    };
+    let record = must_get_valid_record(report_hash)?;
+
+    if record.action().author().ne(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the author of a report can link it".into(),
+        ));
+    }
+
+    let Ok(report) = record_to_entry::<Report>(&record) else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Report links must point to a report".into(),
+        ));
+    };
+
+    let expected_base = match link_type {
+        LinkTypes::TargetToReport => AnyLinkableHash::from(report.target),
+        LinkTypes::InboxToReport => {
+            AnyLinkableHash::from(inbox_path(report.channel).path_entry_hash()?)
+        }
+    };
+
+    match expected_base.eq(&base_address) {
+        true => Ok(ValidateCallbackResult::Valid),
+        false => Ok(ValidateCallbackResult::Invalid(
+            "Reports can only be linked from their target and from the inbox of their channel"
+                .into(),
+        )),
+    }
+}
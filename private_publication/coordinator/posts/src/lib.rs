@@ -1,5 +1,6 @@
 use hdk::prelude::{holo_hash::DnaHash, *};
 use private_publication_integrity::{EntryTypes, LinkTypes, Post};
+use zome_utils::{get_latest_revision, get_latest_revisions, get_records_details, ZomeError};
 
 #[hdk_extern]
 pub fn get_dna_hash(_: ()) -> ExternResult<DnaHash> {
@@ -12,12 +13,21 @@ pub fn get_all_posts(_: ()) -> ExternResult<Vec<Record>> {
 
     let links = get_links(path.path_entry_hash()?, LinkTypes::PathToPost, None)?;
 
-    let records = links
+    let action_hashes: Vec<ActionHash> = links
         .into_iter()
-        .map(|link| get_post(ActionHash::from(link.target)))
-        .collect::<ExternResult<Vec<Record>>>()?;
+        .map(|link| ActionHash::from(link.target))
+        .collect();
 
-    Ok(records)
+    let posts_details = get_records_details(action_hashes, GetOptions::default())?
+        .into_iter()
+        .map(|maybe_details| match maybe_details {
+            Some(Details::Record(element_details)) => Ok(element_details),
+            Some(Details::Entry(_)) => Err(ZomeError::Malformed("Malformed details".into()).into()),
+            None => Err(ZomeError::NotFound("Post not found".into()).into()),
+        })
+        .collect::<ExternResult<Vec<RecordDetails>>>()?;
+
+    get_latest_revisions(posts_details)
 }
 
 #[hdk_extern]
@@ -57,16 +67,5 @@ pub fn get_post(action_hash: ActionHash) -> ExternResult<Record> {
 }
 
 fn get_latest_post(action_hash: ActionHash) -> ExternResult<Record> {
-    let details = get_details(action_hash, GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Post not found".into())))?;
-
-    match details {
-        Details::Entry(_) => Err(wasm_error!(WasmErrorInner::Guest(
-            "Malformed details".into()
-        ))),
-        Details::Record(element_details) => match element_details.updates.last() {
-            Some(update) => get_latest_post(update.action_address().clone()),
-            None => Ok(element_details.record),
-        },
-    }
+    get_latest_revision(action_hash)?.ok_or(ZomeError::NotFound("Post not found".into()).into())
 }
@@ -5,11 +5,12 @@ use hdk::prelude::{
 use private_publication_lobby_integrity::{
     self, EntryTypes, LinkTypes, PrivatePublicationMembraneProof,
 };
+use zome_utils::{decode_zome_call_response, ZomeError};
 
 fn build_secret() -> ExternResult<CapSecret> {
     let bytes = random_bytes(64)?;
     CapSecret::try_from(bytes.into_vec())
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Could not build secret".into())))
+        .map_err(|_| ZomeError::Internal("Could not build secret".into()).into())
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -76,9 +77,10 @@ pub fn read_posts_for_author(author: AgentPubKey) -> ExternResult<Vec<Record>> {
         .collect();
 
     match claims.first() {
-        None => Err(wasm_error!(WasmErrorInner::Guest(
-            "We don't have capability to call this functions".into()
-        ))),
+        None => Err(ZomeError::Unauthorized(
+            "We don't have capability to call this functions".into(),
+        )
+        .into()),
         Some(claim) => {
             let response = call_remote(
                 author,
@@ -88,16 +90,7 @@ pub fn read_posts_for_author(author: AgentPubKey) -> ExternResult<Vec<Record>> {
                 (),
             )?;
 
-            match response {
-                ZomeCallResponse::Ok(result) => {
-                    let posts: Vec<Record> = result.decode().map_err(|err| wasm_error!(err))?;
-
-                    Ok(posts)
-                }
-                _ => Err(wasm_error!(WasmErrorInner::Guest(
-                    format!("Error making the call remote {:?}", response)
-                ))),
-            }
+            decode_zome_call_response("request_read_private_publication_posts", response)
         }
     }
 }
@@ -106,14 +99,16 @@ pub fn read_posts_for_author(author: AgentPubKey) -> ExternResult<Vec<Record>> {
 pub fn request_read_private_publication_posts(_: ()) -> ExternResult<Vec<Record>> {
     let cap_grant = call_info()?.cap_grant;
 
-    let CapGrant::RemoteAgent( zome_call_cap_grant) = cap_grant else {
-        return Err(wasm_error!(WasmErrorInner::Guest(String::from("request_read_all_posts must be called using a cap grant"))));
+    let CapGrant::RemoteAgent(zome_call_cap_grant) = cap_grant else {
+        return Err(ZomeError::Unauthorized(
+            "request_read_all_posts must be called using a cap grant".into(),
+        )
+        .into());
     };
 
     let private_publication_dna_hash = DnaHash::from(
-        DnaHashB64::from_b64_str(zome_call_cap_grant.tag.as_str()).or(Err(wasm_error!(
-            WasmErrorInner::Guest(String::from("Bad cap_grant tag"))
-        )))?,
+        DnaHashB64::from_b64_str(zome_call_cap_grant.tag.as_str())
+            .or(Err(ZomeError::Malformed("Bad cap_grant tag".into())))?,
     );
 
     let private_publication_cell_id = CellId::new(
@@ -129,16 +124,7 @@ pub fn request_read_private_publication_posts(_: ()) -> ExternResult<Vec<Record>
         (),
     )?;
 
-    match response {
-        ZomeCallResponse::Ok(result) => {
-            let posts: Vec<Record> = result.decode().map_err(|err| wasm_error!(err))?;
-
-            Ok(posts)
-        }
-        _ => Err(wasm_error!(WasmErrorInner::Guest(
-            format!("Error making the call remote {:?}", response)
-        ))),
-    }
+    decode_zome_call_response("get_all_posts", response)
 }
 
 /** Exercise 2 */
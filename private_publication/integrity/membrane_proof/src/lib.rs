@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use hdi::prelude::{holo_hash::DnaHash, *};
 
 #[derive(Clone)]
@@ -6,3 +8,70 @@ pub struct PrivatePublicationMembraneProof {
     pub recipient: AgentPubKey,
     pub dna_hash: DnaHash,
 }
+
+// Decodes the record that was given as membrane proof
+pub fn membrane_proof_record(membrane_proof: MembraneProof) -> ExternResult<Record> {
+    let bytes = Arc::try_unwrap(membrane_proof)
+        .map_err(|err| wasm_error!(WasmErrorInner::Guest(format!("{:?}", err))))?;
+
+    Record::try_from(bytes).map_err(|err| wasm_error!(err))
+}
+
+// Checks that the given record is signed by its author and contains a membrane proof
+// for the given agent to join this DNA
+//
+// Whether the author is allowed to issue membrane proofs is left to the caller
+pub fn validate_membrane_proof_record(
+    for_agent: &AgentPubKey,
+    record: &Record,
+) -> ExternResult<ValidateCallbackResult> {
+    if !verify_signature(
+        record.action().author().clone(),
+        record.signature().clone(),
+        record.action_hashed().as_content(),
+    )? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The signature of the record is not valid".into(),
+        ));
+    }
+
+    let maybe_private_publication_membrane_proof: Option<PrivatePublicationMembraneProof> = record
+        .entry()
+        .to_app_option()
+        .map_err(|err| wasm_error!(err))?;
+
+    let Some(private_publication_membrane_proof) = maybe_private_publication_membrane_proof else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Malformed membrane proof".into(),
+        ));
+    };
+
+    let actual_entry_hash = hash_entry(&private_publication_membrane_proof)?;
+    let entry_hash_in_action = record
+        .action()
+        .entry_hash()
+        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
+            "The given record doesn't contain an entry hash"
+        ))))?
+        .clone();
+
+    if !entry_hash_in_action.eq(&actual_entry_hash) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The entry hash is not valid for the given entry".into(),
+        ));
+    }
+
+    if private_publication_membrane_proof.dna_hash != dna_info()?.hash {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The membrane proof is not for this dna".into(),
+        ));
+    }
+
+    if !private_publication_membrane_proof.recipient.eq(for_agent) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The membrane proof is not for this agent".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,25 @@
+use hdk::prelude::*;
+
+// Mirrors the file manifest entry defined in the files zome, which attachments point to
+#[hdk_entry_helper]
+pub struct FileManifest {
+    pub name: String,
+    pub mime_type: String,
+    pub size: usize,
+    pub chunks: Vec<EntryHash>,
+}
+
+// Attachments can only point to the manifest of a file uploaded to the files zome
+pub fn validate_attachments(attachments: &[EntryHash]) -> ExternResult<ValidateCallbackResult> {
+    for attachment in attachments {
+        let entry = must_get_entry(attachment.clone())?;
+
+        if FileManifest::try_from(entry.content).is_err() {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Attachments can only point to file manifests".into(),
+            ));
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,28 @@
+use hdk::prelude::*;
+
+use crate::errors::decode_zome_call_response;
+
+// Name of the zome in this DNA that holds the profiles of the agents
+const PROFILES_ZOME_NAME: &str = "profiles_zome";
+
+// Calls the given function in another zome of this same cell
+pub fn call_local_zome<I, O>(zome_name: &str, fn_name: &str, payload: I) -> ExternResult<O>
+where
+    I: Serialize + std::fmt::Debug,
+    O: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from(zome_name),
+        fn_name.into(),
+        None,
+        payload,
+    )?;
+
+    decode_zome_call_response(fn_name, response)
+}
+
+// Gets the agent that created a profile with the given nickname, if exactly one agent did
+pub fn get_agent_for_nickname(nickname: String) -> ExternResult<Option<AgentPubKey>> {
+    call_local_zome(PROFILES_ZOME_NAME, "get_agent_for_nickname", nickname)
+}
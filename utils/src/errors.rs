@@ -0,0 +1,118 @@
+use hdk::prelude::*;
+
+// Errors returned by the zome functions
+//
+// They travel to the clients serialized as JSON inside the guest error, so that they can be
+// handled by variant, e.g. `{"type":"NotFound","details":"Post not found"}`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", content = "details")]
+pub enum ZomeError {
+    NotFound(String),
+    Unauthorized(String),
+    Malformed(String),
+    InvalidRequest(String),
+    RemoteCallFailed { fn_name: String, reason: String },
+    Internal(String),
+}
+
+impl ZomeError {
+    // Recovers the zome error carried by the given wasm error, if there is one
+    pub fn from_wasm_error(error: &WasmError) -> Option<ZomeError> {
+        match &error.error {
+            WasmErrorInner::Guest(message) => serde_json::from_str(message).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl From<ZomeError> for WasmError {
+    fn from(error: ZomeError) -> Self {
+        let message = serde_json::to_string(&error).unwrap_or_else(|_| format!("{:?}", error));
+
+        wasm_error!(WasmErrorInner::Guest(message))
+    }
+}
+
+// Decodes the output of a successful call to another zome or cell, or turns the response into an error
+pub fn decode_zome_call_response<O>(fn_name: &str, response: ZomeCallResponse) -> ExternResult<O>
+where
+    O: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|err| {
+            ZomeError::Malformed(format!("Malformed output from {}: {:?}", fn_name, err)).into()
+        }),
+        _ => Err(ZomeError::RemoteCallFailed {
+            fn_name: String::from(fn_name),
+            reason: format!("{:?}", response),
+        }
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zome_errors_are_tagged_json() {
+        let error = WasmError::from(ZomeError::NotFound("Post not found".into()));
+
+        assert_eq!(
+            error.error,
+            WasmErrorInner::Guest(r#"{"type":"NotFound","details":"Post not found"}"#.into())
+        );
+    }
+
+    #[test]
+    fn zome_errors_round_trip_through_wasm_errors() {
+        let error = ZomeError::RemoteCallFailed {
+            fn_name: "get_agent_profile".into(),
+            reason: "NetworkError".into(),
+        };
+
+        let wasm_error = WasmError::from(error.clone());
+
+        assert_eq!(ZomeError::from_wasm_error(&wasm_error), Some(error));
+    }
+
+    #[test]
+    fn other_wasm_errors_are_not_zome_errors() {
+        let wasm_error = wasm_error!(WasmErrorInner::Guest("Free-form error".into()));
+
+        assert_eq!(ZomeError::from_wasm_error(&wasm_error), None);
+    }
+
+    #[test]
+    fn decodes_successful_responses() {
+        let response = ZomeCallResponse::Ok(ExternIO::encode(42u32).unwrap());
+
+        let output: u32 = decode_zome_call_response("answer", response).unwrap();
+
+        assert_eq!(output, 42);
+    }
+
+    #[test]
+    fn undecodable_responses_are_malformed_errors() {
+        let response = ZomeCallResponse::Ok(ExternIO::encode("forty-two").unwrap());
+
+        let error = decode_zome_call_response::<u32>("answer", response).unwrap_err();
+
+        assert!(matches!(
+            ZomeError::from_wasm_error(&error),
+            Some(ZomeError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn failed_responses_are_remote_call_errors() {
+        let response = ZomeCallResponse::NetworkError("Agent unreachable".into());
+
+        let error = decode_zome_call_response::<u32>("answer", response).unwrap_err();
+
+        assert!(matches!(
+            ZomeError::from_wasm_error(&error),
+            Some(ZomeError::RemoteCallFailed { fn_name, .. }) if fn_name == "answer"
+        ));
+    }
+}
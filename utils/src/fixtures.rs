@@ -0,0 +1,88 @@
+use hdk::prelude::*;
+
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct TestEntry {
+    pub value: String,
+}
+
+pub fn fake_action_hash(seed: u8) -> ActionHash {
+    ActionHash::from_raw_36(vec![seed; 36])
+}
+
+fn fake_entry_hash(seed: u8) -> EntryHash {
+    EntryHash::from_raw_36(vec![seed; 36])
+}
+
+fn fake_agent() -> AgentPubKey {
+    AgentPubKey::from_raw_36(vec![0; 36])
+}
+
+// Builds a record with the action hash given by the seed, updating the record with the
+// `updates` seed if given
+pub fn fake_record(seed: u8, updates: Option<u8>, entry: Option<&TestEntry>) -> Record {
+    let entry_type = EntryType::App(AppEntryDef::new(
+        EntryDefIndex(0),
+        ZomeIndex(0),
+        EntryVisibility::Public,
+    ));
+
+    let action = match updates {
+        None => Action::Create(Create {
+            author: fake_agent(),
+            timestamp: Timestamp::from_micros(seed as i64),
+            action_seq: seed as u32,
+            prev_action: fake_action_hash(0),
+            entry_type,
+            entry_hash: fake_entry_hash(seed),
+            weight: Default::default(),
+        }),
+        Some(original_seed) => Action::Update(Update {
+            author: fake_agent(),
+            timestamp: Timestamp::from_micros(seed as i64),
+            action_seq: seed as u32,
+            prev_action: fake_action_hash(0),
+            original_action_address: fake_action_hash(original_seed),
+            original_entry_address: fake_entry_hash(original_seed),
+            entry_type,
+            entry_hash: fake_entry_hash(seed),
+            weight: Default::default(),
+        }),
+    };
+
+    let signed_action = SignedActionHashed::with_presigned(
+        ActionHashed::with_pre_hashed(action, fake_action_hash(seed)),
+        Signature([0; 64]),
+    );
+
+    let entry = entry.map(|entry| {
+        Entry::App(AppEntryBytes(
+            SerializedBytes::try_from(entry.clone()).unwrap(),
+        ))
+    });
+
+    Record::new(signed_action, entry)
+}
+
+pub fn fake_record_details(record: Record, updates: Vec<Record>) -> RecordDetails {
+    RecordDetails {
+        record,
+        validation_status: ValidationStatus::Valid,
+        deletes: vec![],
+        updates: updates
+            .into_iter()
+            .map(|update| update.signed_action().clone())
+            .collect(),
+    }
+}
+
+pub fn fake_link(target: ActionHash) -> Link {
+    Link {
+        author: fake_agent(),
+        target: target.into(),
+        timestamp: Timestamp::from_micros(0),
+        zome_index: ZomeIndex(0),
+        link_type: LinkType(0),
+        tag: LinkTag::new(vec![]),
+        create_link_hash: fake_action_hash(0),
+    }
+}
@@ -0,0 +1,32 @@
+mod attachments;
+mod calls;
+mod errors;
+mod links;
+mod mentions;
+mod properties;
+mod rate_limit;
+mod records;
+mod signals;
+mod source_chain;
+
+#[cfg(test)]
+mod fixtures;
+
+pub use attachments::{validate_attachments, FileManifest};
+pub use calls::{call_local_zome, get_agent_for_nickname};
+pub use errors::{decode_zome_call_response, ZomeError};
+pub use links::{get_link_details_batch, get_links_batch, links_to_records};
+pub use mentions::{
+    create_mention_links, get_my_mentions, parse_mentions, validate_create_link_mention, Mention,
+};
+pub use properties::{dna_properties, is_moderator, moderators};
+pub use rate_limit::{validate_rate_limit, RateLimit};
+pub use records::{
+    get_latest_revision, get_latest_revisions, get_records, get_records_details, get_typed_entry,
+    is_post_or_poll, record_to_entry,
+};
+pub use signals::{
+    grant_unrestricted_access, signal_subscribers, unsubscribe, validate_create_link_subscriber,
+    validate_delete_link_subscriber,
+};
+pub use source_chain::walk_source_chain;
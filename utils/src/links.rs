@@ -0,0 +1,90 @@
+use hdk::prelude::*;
+
+use crate::records::get_records;
+
+// Gets the links of the given type for all the given bases in a single batched call to the host
+pub fn get_links_batch<H>(
+    bases: Vec<H>,
+    link_type: impl LinkTypeFilterExt,
+) -> ExternResult<Vec<Vec<Link>>>
+where
+    AnyLinkableHash: From<H>,
+{
+    let link_type = link_type.try_into_filter()?;
+
+    let inputs: Vec<GetLinksInput> = bases
+        .into_iter()
+        .map(|base| GetLinksInput::new(AnyLinkableHash::from(base), link_type.clone(), None))
+        .collect();
+
+    HDK.with(|h| h.borrow().get_links(inputs))
+}
+
+// Gets the details of the links of the given type, including their deletes, for all the given bases
+// in a single batched call to the host
+pub fn get_link_details_batch<H>(
+    bases: Vec<H>,
+    link_type: impl LinkTypeFilterExt,
+) -> ExternResult<Vec<LinkDetails>>
+where
+    AnyLinkableHash: From<H>,
+{
+    let link_type = link_type.try_into_filter()?;
+
+    let inputs: Vec<GetLinksInput> = bases
+        .into_iter()
+        .map(|base| GetLinksInput::new(AnyLinkableHash::from(base), link_type.clone(), None))
+        .collect();
+
+    HDK.with(|h| h.borrow().get_link_details(inputs))
+}
+
+// Gets the records that the given links point to, skipping the ones that can't be found
+//
+// The links are expected to target action hashes
+pub fn links_to_records(links: Vec<Link>, options: GetOptions) -> ExternResult<Vec<Record>> {
+    let targets: Vec<ActionHash> = links
+        .into_iter()
+        .map(|link| ActionHash::from(link.target))
+        .collect();
+
+    let records = get_records(targets, options)?;
+
+    Ok(records.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{fake_action_hash, fake_link, fake_record};
+
+    #[test]
+    fn links_to_records_skips_missing_records() {
+        let found = fake_record(1, None, None);
+        let found_hash = found.action_address().clone();
+
+        let mut mock_hdk = MockHdkT::new();
+        mock_hdk.expect_get().returning(move |inputs| {
+            Ok(inputs
+                .into_iter()
+                .map(
+                    |input| match input.any_dht_hash == found_hash.clone().into() {
+                        true => Some(found.clone()),
+                        false => None,
+                    },
+                )
+                .collect())
+        });
+        set_hdk(mock_hdk);
+
+        let links = vec![
+            fake_link(fake_action_hash(1)),
+            fake_link(fake_action_hash(2)),
+        ];
+
+        let records = links_to_records(links, GetOptions::default()).unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].action_address(), &fake_action_hash(1));
+    }
+}
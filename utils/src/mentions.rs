@@ -0,0 +1,116 @@
+use hdk::prelude::*;
+
+use crate::calls::get_agent_for_nickname;
+
+// Some content that mentioned an agent, as found in the links to that agent
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Mention {
+    pub mentioned_in: ActionHash,
+    pub mentioned_by: AgentPubKey,
+    pub timestamp: Timestamp,
+}
+
+// Gets the nicknames mentioned as "@nickname" in the given text, without duplicates
+pub fn parse_mentions(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-' || c == '@'))
+        .filter_map(|word| word.strip_prefix('@'))
+        .filter(|nickname| !nickname.is_empty() && !nickname.contains('@'))
+        .map(String::from)
+        .collect()
+}
+
+// Links the given content from all the agents mentioned by nickname in its text
+//
+// Nicknames that were already mentioned in the previous revision of the text are skipped, so that
+// an update only notifies the agents it mentions for the first time
+pub fn create_mention_links<T, E>(
+    text: &str,
+    previous_text: Option<&str>,
+    mentioned_in: ActionHash,
+    link_type: T,
+) -> ExternResult<()>
+where
+    T: Copy,
+    ScopedLinkType: TryFrom<T, Error = E>,
+    WasmError: From<E>,
+{
+    let already_mentioned = previous_text.map(parse_mentions).unwrap_or_default();
+
+    for nickname in parse_mentions(text).difference(&already_mentioned) {
+        if let Some(agent) = get_agent_for_nickname(nickname.clone())? {
+            create_link(agent, mentioned_in.clone(), link_type, ())?;
+        }
+    }
+
+    Ok(())
+}
+
+// Gets the content that has mentioned us since the given time, newest first
+pub fn get_my_mentions(
+    since: Timestamp,
+    link_type: impl LinkTypeFilterExt,
+) -> ExternResult<Vec<Mention>> {
+    let mut links = get_links(agent_info()?.agent_initial_pubkey, link_type, None)?;
+
+    links.retain(|link| link.timestamp >= since);
+    links.sort_by_key(|link| std::cmp::Reverse(link.timestamp));
+
+    Ok(links
+        .into_iter()
+        .map(|link| Mention {
+            mentioned_in: ActionHash::from(link.target),
+            mentioned_by: link.author,
+            timestamp: link.timestamp,
+        })
+        .collect())
+}
+
+// Mentions can only point to content written by the agent that created the mention link,
+// and only to the kind of content accepted by `is_mentionable`
+pub fn validate_create_link_mention(
+    action: &CreateLink,
+    target_address: AnyLinkableHash,
+    is_mentionable: impl FnOnce(&Record) -> ExternResult<bool>,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(mentioned_in) = target_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Mentions must point to an action hash".into(),
+        ));
+    };
+    let record = must_get_valid_record(mentioned_in)?;
+
+    if record.action().author().ne(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Agents can only create mentions from their own content".into(),
+        ));
+    }
+
+    match is_mentionable(&record)? {
+        true => Ok(ValidateCallbackResult::Valid),
+        false => Ok(ValidateCallbackResult::Invalid(
+            "Mentions can't point to this kind of content".into(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_mentions_once() {
+        let mentions = parse_mentions("Thanks @alice, and @bob_2! cc @alice");
+
+        assert_eq!(
+            mentions,
+            BTreeSet::from([String::from("alice"), String::from("bob_2")])
+        );
+    }
+
+    #[test]
+    fn ignores_emails_and_lone_at_signs() {
+        let mentions = parse_mentions("Write to alice@example.com @ any time");
+
+        assert!(mentions.is_empty());
+    }
+}
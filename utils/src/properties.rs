@@ -0,0 +1,38 @@
+use hdk::prelude::{holo_hash::AgentPubKeyB64, *};
+
+use crate::errors::ZomeError;
+
+// Reads the forum properties from the DNA, falling back to the defaults if none were given
+//
+// Each zome only declares the properties it needs, the rest are ignored
+pub fn dna_properties<P>() -> ExternResult<P>
+where
+    P: serde::de::DeserializeOwned + Default,
+{
+    let properties = dna_info()?.properties;
+    let maybe_properties: Option<P> = decode(properties.bytes())
+        .map_err(|err| ZomeError::Malformed(format!("Malformed DNA properties: {:?}", err)))?;
+
+    Ok(maybe_properties.unwrap_or_default())
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ModerationProperties {
+    #[serde(default)]
+    moderators: Vec<AgentPubKeyB64>,
+}
+
+// Gets the agents that were designated as moderators in the DNA properties
+pub fn moderators() -> ExternResult<Vec<AgentPubKey>> {
+    let properties: ModerationProperties = dna_properties()?;
+
+    Ok(properties
+        .moderators
+        .into_iter()
+        .map(AgentPubKey::from)
+        .collect())
+}
+
+pub fn is_moderator(agent: &AgentPubKey) -> ExternResult<bool> {
+    Ok(moderators()?.contains(agent))
+}
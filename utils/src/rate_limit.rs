@@ -0,0 +1,89 @@
+use hdk::prelude::*;
+
+use crate::source_chain::walk_source_chain;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RateLimit {
+    pub max_entries: u32,
+    pub period_seconds: u64,
+}
+
+// Checks that the author hasn't created more than the allowed entries of the same type
+// within the rate limit period that ends at the given action
+pub fn validate_rate_limit(
+    action: &Create,
+    rate_limit: &RateLimit,
+) -> ExternResult<ValidateCallbackResult> {
+    let window_start = action
+        .timestamp
+        .as_micros()
+        .saturating_sub((rate_limit.period_seconds as i64).saturating_mul(1_000_000));
+
+    // The given action is the first entry in the window
+    let mut entries_in_window: u32 = 1;
+
+    if entries_in_window > rate_limit.max_entries {
+        return Ok(rate_limit_exceeded(rate_limit));
+    }
+
+    let exceeded = walk_source_chain(
+        &action.author,
+        action.prev_action.clone(),
+        |previous_action| {
+            if previous_action.timestamp().as_micros() < window_start {
+                return Some(false);
+            }
+
+            if let Action::Create(create) = previous_action {
+                if create.entry_type == action.entry_type {
+                    entries_in_window += 1;
+                }
+            }
+
+            (entries_in_window > rate_limit.max_entries).then_some(true)
+        },
+    )?;
+
+    match exceeded {
+        Some(true) => Ok(rate_limit_exceeded(rate_limit)),
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+fn rate_limit_exceeded(rate_limit: &RateLimit) -> ValidateCallbackResult {
+    ValidateCallbackResult::Invalid(format!(
+        "Only {} entries of this type can be created every {} seconds",
+        rate_limit.max_entries, rate_limit.period_seconds
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{fake_action_hash, fake_record};
+
+    fn fake_create() -> Create {
+        match fake_record(2, None, None).action().clone() {
+            Action::Create(create) => Create {
+                prev_action: fake_action_hash(1),
+                ..create
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn zero_entries_are_rejected_without_walking_the_chain() {
+        // No expectations: any call to the host would panic
+        set_hdk(MockHdkT::new());
+
+        let rate_limit = RateLimit {
+            max_entries: 0,
+            period_seconds: 60,
+        };
+
+        let result = validate_rate_limit(&fake_create(), &rate_limit).unwrap();
+
+        assert!(matches!(result, ValidateCallbackResult::Invalid(_)));
+    }
+}
@@ -0,0 +1,240 @@
+use hdk::prelude::*;
+
+use crate::errors::ZomeError;
+
+// Name of the zome in this DNA that defines the posts and the polls
+const POSTS_ZOME_NAME: &str = "posts_zome";
+
+// Gets all the given records in a single batched call to the host
+pub fn get_records<H>(hashes: Vec<H>, options: GetOptions) -> ExternResult<Vec<Option<Record>>>
+where
+    AnyDhtHash: From<H>,
+{
+    let inputs: Vec<GetInput> = hashes
+        .into_iter()
+        .map(|hash| GetInput::new(AnyDhtHash::from(hash), options.clone()))
+        .collect();
+
+    HDK.with(|h| h.borrow().get(inputs))
+}
+
+// Gets the details of all the given records in a single batched call to the host
+pub fn get_records_details<H>(
+    hashes: Vec<H>,
+    options: GetOptions,
+) -> ExternResult<Vec<Option<Details>>>
+where
+    AnyDhtHash: From<H>,
+{
+    let inputs: Vec<GetInput> = hashes
+        .into_iter()
+        .map(|hash| GetInput::new(AnyDhtHash::from(hash), options.clone()))
+        .collect();
+
+    HDK.with(|h| h.borrow().get_details(inputs))
+}
+
+// Deserializes the app entry contained in the given record
+pub fn record_to_entry<T>(record: &Record) -> ExternResult<T>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|err| ZomeError::Malformed(format!("Malformed entry: {:?}", err)))?
+        .ok_or(ZomeError::Malformed("The record doesn't contain an app entry".into()).into())
+}
+
+// Gets the record with the given hash and deserializes its app entry, if it can be found
+pub fn get_typed_entry<T, H>(hash: H, options: GetOptions) -> ExternResult<Option<T>>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    AnyDhtHash: From<H>,
+{
+    get(hash, options)?
+        .map(|record| record_to_entry(&record))
+        .transpose()
+}
+
+// Whether the given record creates a post or a poll in the posts zome
+//
+// These are the only public entries of the posts zome, the rest are private to their author,
+// so the check doesn't depend on the order of its entry defs
+pub fn is_post_or_poll(record: &Record) -> ExternResult<bool> {
+    let Some(EntryType::App(app_entry_def)) = record.action().entry_type() else {
+        return Ok(false);
+    };
+
+    if app_entry_def.visibility != EntryVisibility::Public {
+        return Ok(false);
+    }
+
+    let zome_names = dna_info()?.zome_names;
+
+    Ok(zome_names
+        .get(app_entry_def.zome_index.0 as usize)
+        .map(|zome_name| zome_name.0 == POSTS_ZOME_NAME)
+        .unwrap_or(false))
+}
+
+// Follows the updates of the given record until its latest revision, if it can be found
+pub fn get_latest_revision(action_hash: ActionHash) -> ExternResult<Option<Record>> {
+    let mut revision_hash = action_hash;
+
+    loop {
+        match get_details(revision_hash, GetOptions::default())? {
+            None => return Ok(None),
+            Some(Details::Entry(_)) => {
+                return Err(ZomeError::Malformed("Malformed details".into()).into())
+            }
+            Some(Details::Record(record_details)) => match record_details.updates.last() {
+                Some(update) => revision_hash = update.action_address().clone(),
+                None => return Ok(Some(record_details.record)),
+            },
+        }
+    }
+}
+
+// Follows the updates of each of the given records until their latest revision
+//
+// All the records are walked at the same time, fetching each level of updates in a single batched call
+pub fn get_latest_revisions(records_details: Vec<RecordDetails>) -> ExternResult<Vec<Record>> {
+    let mut latest_revisions: Vec<Option<Record>> = vec![None; records_details.len()];
+    let mut pending_updates: Vec<(usize, ActionHash)> = vec![];
+
+    for (index, record_details) in records_details.into_iter().enumerate() {
+        match record_details.updates.last() {
+            Some(update) => pending_updates.push((index, update.action_address().clone())),
+            None => latest_revisions[index] = Some(record_details.record),
+        }
+    }
+
+    if !pending_updates.is_empty() {
+        let update_hashes: Vec<ActionHash> = pending_updates
+            .iter()
+            .map(|(_, update_hash)| update_hash.clone())
+            .collect();
+
+        let updates_details = get_records_details(update_hashes, GetOptions::default())?
+            .into_iter()
+            .map(|maybe_details| match maybe_details {
+                Some(Details::Record(update_details)) => Ok(update_details),
+                Some(Details::Entry(_)) => {
+                    Err(ZomeError::Malformed("Malformed details".into()).into())
+                }
+                None => Err(ZomeError::NotFound("Update not found".into()).into()),
+            })
+            .collect::<ExternResult<Vec<RecordDetails>>>()?;
+
+        for ((index, _), latest_revision) in pending_updates
+            .into_iter()
+            .zip(get_latest_revisions(updates_details)?)
+        {
+            latest_revisions[index] = Some(latest_revision);
+        }
+    }
+
+    Ok(latest_revisions.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{fake_action_hash, fake_record, fake_record_details, TestEntry};
+
+    // Serves the details of the given records, looking them up by action hash
+    fn mock_get_details(records_details: Vec<RecordDetails>) {
+        let mut mock_hdk = MockHdkT::new();
+        mock_hdk.expect_get_details().returning(move |inputs| {
+            Ok(inputs
+                .into_iter()
+                .map(|input| {
+                    records_details
+                        .iter()
+                        .find(|details| {
+                            AnyDhtHash::from(details.record.action_address().clone())
+                                == input.any_dht_hash
+                        })
+                        .cloned()
+                        .map(Details::Record)
+                })
+                .collect())
+        });
+        set_hdk(mock_hdk);
+    }
+
+    #[test]
+    fn record_to_entry_deserializes_the_entry() {
+        let entry = TestEntry {
+            value: String::from("hello"),
+        };
+        let record = fake_record(1, None, Some(&entry));
+
+        let deserialized: TestEntry = record_to_entry(&record).unwrap();
+
+        assert_eq!(deserialized, entry);
+    }
+
+    #[test]
+    fn record_to_entry_fails_without_entry() {
+        let record = fake_record(1, None, None);
+
+        assert!(record_to_entry::<TestEntry>(&record).is_err());
+    }
+
+    #[test]
+    fn latest_revision_follows_the_updates() {
+        let original = fake_record(1, None, None);
+        let first_update = fake_record(2, Some(1), None);
+        let second_update = fake_record(3, Some(2), None);
+
+        mock_get_details(vec![
+            fake_record_details(original, vec![first_update.clone()]),
+            fake_record_details(first_update, vec![second_update.clone()]),
+            fake_record_details(second_update, vec![]),
+        ]);
+
+        let latest = get_latest_revision(fake_action_hash(1)).unwrap().unwrap();
+
+        assert_eq!(latest.action_address(), &fake_action_hash(3));
+    }
+
+    #[test]
+    fn latest_revision_of_missing_record_is_none() {
+        mock_get_details(vec![]);
+
+        assert!(get_latest_revision(fake_action_hash(1)).unwrap().is_none());
+    }
+
+    #[test]
+    fn latest_revisions_keep_the_order_of_the_records() {
+        let updated = fake_record(1, None, None);
+        let update = fake_record(2, Some(1), None);
+        let not_updated = fake_record(3, None, None);
+
+        mock_get_details(vec![fake_record_details(update.clone(), vec![])]);
+
+        let latest = get_latest_revisions(vec![
+            fake_record_details(updated, vec![update]),
+            fake_record_details(not_updated, vec![]),
+        ])
+        .unwrap();
+
+        let hashes: Vec<&ActionHash> = latest
+            .iter()
+            .map(|record| record.action_address())
+            .collect();
+        assert_eq!(hashes, vec![&fake_action_hash(2), &fake_action_hash(3)]);
+    }
+
+    #[test]
+    fn latest_revisions_fail_when_an_update_is_missing() {
+        let updated = fake_record(1, None, None);
+        let update = fake_record(2, Some(1), None);
+
+        mock_get_details(vec![]);
+
+        assert!(get_latest_revisions(vec![fake_record_details(updated, vec![update])]).is_err());
+    }
+}
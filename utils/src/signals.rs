@@ -0,0 +1,82 @@
+use hdk::prelude::*;
+
+// Allows any agent to call the given function of the current zome, to be done in `init`
+pub fn grant_unrestricted_access(fn_name: &str) -> ExternResult<()> {
+    let functions = GrantedFunctions::Listed(BTreeSet::from([(
+        zome_info()?.name,
+        FunctionName::from(fn_name),
+    )]));
+
+    create_cap_grant(CapGrantEntry {
+        tag: String::from(fn_name),
+        access: CapAccess::Unrestricted,
+        functions,
+    })?;
+
+    Ok(())
+}
+
+// Removes our own subscriber links from the given base
+pub fn unsubscribe(
+    base: impl Into<AnyLinkableHash>,
+    link_type: impl LinkTypeFilterExt,
+) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_initial_pubkey;
+
+    let links = get_links(base, link_type, None)?;
+
+    for link in links {
+        if link.author == my_pub_key {
+            delete_link(link.create_link_hash)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Emits the given signal to ourselves and sends it to the agents subscribed to the given base
+pub fn signal_subscribers<S>(
+    signal: &S,
+    base: impl Into<AnyLinkableHash>,
+    link_type: impl LinkTypeFilterExt,
+) -> ExternResult<()>
+where
+    S: Serialize + std::fmt::Debug,
+{
+    let my_pub_key = agent_info()?.agent_initial_pubkey;
+
+    emit_signal(signal)?;
+
+    let subscribers: Vec<AgentPubKey> = get_links(base, link_type, None)?
+        .into_iter()
+        .map(|link| AgentPubKey::from(EntryHash::from(link.target)))
+        .filter(|subscriber| subscriber.ne(&my_pub_key))
+        .collect();
+
+    remote_signal(signal, subscribers)
+}
+
+// Agents can only subscribe themselves
+pub fn validate_create_link_subscriber(
+    action: CreateLink,
+    target_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    match AnyLinkableHash::from(action.author).eq(&target_address) {
+        true => Ok(ValidateCallbackResult::Valid),
+        false => Ok(ValidateCallbackResult::Invalid(String::from(
+            "Agents can only subscribe themselves",
+        ))),
+    }
+}
+
+pub fn validate_delete_link_subscriber(
+    action: DeleteLink,
+    original_action: CreateLink,
+) -> ExternResult<ValidateCallbackResult> {
+    match original_action.author.eq(&action.author) {
+        true => Ok(ValidateCallbackResult::Valid),
+        false => Ok(ValidateCallbackResult::Invalid(String::from(
+            "Only the subscribed agent can unsubscribe",
+        ))),
+    }
+}
@@ -0,0 +1,36 @@
+use hdk::prelude::*;
+
+// Number of actions fetched from the author's source chain at each step of the walk
+const ACTIVITY_PAGE_SIZE: u32 = 20;
+
+// Walks the source chain of the given author backwards, starting at the given action,
+// until `visit` returns a result for one of the actions or the chain ends
+//
+// The walk is bounded by hashes at every step, so all validators reach the same result
+pub fn walk_source_chain<T>(
+    author: &AgentPubKey,
+    chain_top: ActionHash,
+    mut visit: impl FnMut(&Action) -> Option<T>,
+) -> ExternResult<Option<T>> {
+    let mut next_chain_top = Some(chain_top);
+
+    while let Some(chain_top) = next_chain_top.take() {
+        let mut activity = must_get_agent_activity(
+            author.clone(),
+            ChainFilter::new(chain_top).take(ACTIVITY_PAGE_SIZE),
+        )?;
+        activity.sort_by_key(|activity| std::cmp::Reverse(activity.action.action().action_seq()));
+
+        for activity in activity {
+            let action = activity.action.action();
+
+            if let Some(result) = visit(action) {
+                return Ok(Some(result));
+            }
+
+            next_chain_top = action.prev_action().cloned();
+        }
+    }
+
+    Ok(None)
+}